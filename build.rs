@@ -1,99 +1,48 @@
 #[cfg(feature = "ultralight-resources")]
-use std::env::var;
+use std::env::{var, var_os};
 #[cfg(feature = "ultralight-resources")]
-use std::fs::{self, DirEntry};
+use std::fs;
 #[cfg(feature = "ultralight-resources")]
-use std::path::Path;
+use std::path::PathBuf;
 
-fn main() {
-    // ensure runtime resources exist - for examples & local tests
-    #[cfg(feature = "ultralight-resources")]
-    {
-        let mut possible_directories = Vec::new();
-        let out = var("OUT_DIR").unwrap();
-        // This allows it to work in this project but also other projects too
-        let path = Path::new(&out)
-            .parent()
-            .unwrap()
-            .parent()
-            .unwrap()
-            .parent()
-            .unwrap()
-            .parent()
-            .unwrap()
-            .parent()
-            .unwrap();
-
-        let target = Path::new(path).join("target");
-        let debug_path = target.clone().join("debug");
-        let release_path = target.clone().join("release");
-
-        if let Ok(debug) = fs::exists(debug_path.clone()) {
-            if debug {
-                get_paths(
-                    &mut possible_directories,
-                    debug_path.join("build").to_str().unwrap().to_string(),
-                )
-            }
-        } else if let Ok(release) = fs::exists(release_path.clone()) {
-            if release {
-                get_paths(
-                    &mut possible_directories,
-                    release_path.join("build").to_str().unwrap().to_string(),
-                )
-            }
-        } else {
-            panic!("Could not find either debug or release dirs")
-        }
-
-        assert!(!possible_directories.is_empty());
-
-        let local_resources = Path::new(path).join("resources");
+// The runtime files Ultralight needs from its sdk
+#[cfg(feature = "ultralight-resources")]
+const RESOURCES: [&str; 2] = ["cacert.pem", "icudt67l.dat"];
 
-        for path in possible_directories {
-            let resources_dir = path.path().join("out/ul-sdk/resources");
-            if let Ok(resources) = fs::exists(resources_dir.clone()) {
-                if resources {
-                    if let Ok(local_resources_exist) = fs::exists(local_resources.clone()) {
-                        if local_resources_exist {
-                            fs::remove_file(local_resources.clone())
-                                .expect("Failed to delete resources dir")
-                        }
-                    }
+// Same variable the engine reads at runtime, see `RESOURCES_ENV_VAR`
+#[cfg(feature = "ultralight-resources")]
+const RESOURCES_ENV_VAR: &str = "ICY_BROWSER_ULTRALIGHT_RESOURCES";
 
-                    #[cfg(unix)]
-                    {
-                        std::os::unix::fs::symlink(resources_dir, local_resources)
-                            .expect("Failed to sym link resource dir")
-                    }
+fn main() {
+    println!("cargo::rustc-check-cfg=cfg(ultralight_resources_embedded)");
 
-                    break;
+    // copy the sdk resources next to this crate's build output so they can be embedded
+    #[cfg(feature = "ultralight-resources")]
+    {
+        println!("cargo:rerun-if-env-changed={}", RESOURCES_ENV_VAR);
+
+        let out = PathBuf::from(var("OUT_DIR").unwrap());
+        let embedded = out.join("ul-resources");
+
+        match var_os(RESOURCES_ENV_VAR).map(PathBuf::from) {
+            Some(resources) => {
+                fs::create_dir_all(&embedded).expect("Failed to create resources dir");
+                for file in RESOURCES {
+                    let resource = resources.join(file);
+                    println!("cargo:rerun-if-changed={}", resource.display());
+                    fs::copy(&resource, embedded.join(file)).unwrap_or_else(|error| {
+                        panic!("Failed to copy {}: {}", resource.display(), error)
+                    });
                 }
-            } else {
-                panic!("The resouce dir entered has not resources")
+                println!("cargo::rustc-cfg=ultralight_resources_embedded");
             }
+            None => println!(
+                "cargo::warning=Set {} to the ultralight sdk's resources directory to embed them, \
+                otherwise they will have to be provided at runtime",
+                RESOURCES_ENV_VAR
+            ),
         }
     }
 
-    println!("cargo:rerun-if-changed=target");
     println!("cargo:rerun-if-changed=build.rs");
-    println!("cargo:rerun-if-changed=Cargo.lock");
-}
-
-#[cfg(feature = "ultralight-resources")]
-fn get_paths(possible_paths: &mut Vec<fs::DirEntry>, path_str: String) {
-    let mut paths: Vec<DirEntry> = fs::read_dir(path_str)
-        .expect("Could not read dir")
-        .map(|f| f.unwrap())
-        .filter(|file| file.path().to_string_lossy().contains("ul-next-sys"))
-        .collect();
-    // TODO: check if sort working
-    paths.sort_by(|a, b| {
-        a.metadata()
-            .unwrap()
-            .modified()
-            .unwrap()
-            .cmp(&b.metadata().unwrap().modified().unwrap())
-    });
-    possible_paths.append(&mut paths);
 }
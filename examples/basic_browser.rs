@@ -1,11 +1,15 @@
 // Simple browser with familiar browser widgets and the ultralight(webkit) webengine as a backend
 
 use iced::{Settings, Task, Theme};
-use icy_browser::{get_fonts, Bookmark, IcyBrowser, Message, Ultralight};
+use icy_browser::{get_fonts, Bookmark, IcyBrowser, Message, Ultralight, UltralightConfig};
 
 fn run() -> (IcyBrowser<Ultralight>, Task<Message>) {
+    let engine = Ultralight::try_with_config(UltralightConfig::default()).unwrap_or_else(|error| {
+        eprintln!("{}", error);
+        std::process::exit(1)
+    });
     (
-        IcyBrowser::new()
+        IcyBrowser::from_engine(engine)
             .with_tab_bar()
             .with_nav_bar()
             .bookmarks(&[Bookmark::new("https://www.rust-lang.org", "rust-lang.org")])
@@ -5,7 +5,7 @@ use iced::{time, Border};
 use iced::{Color, Element, Length, Settings, Subscription, Task, Theme};
 use std::time::{Duration, Instant};
 
-use icy_browser::{get_fonts, widgets, IcyBrowser, Ultralight};
+use icy_browser::{get_fonts, widgets, IcyBrowser, Ultralight, UltralightConfig};
 
 fn main() -> iced::Result {
     let settings = Settings {
@@ -23,7 +23,6 @@ fn main() -> iced::Result {
 #[derive(Debug, Clone)]
 pub enum Message {
     IcyBrowser(widgets::Message),
-    Update,
     Tick,
 }
 
@@ -40,8 +39,16 @@ struct Browser {
 
 impl Default for Browser {
     fn default() -> Self {
+        let engine =
+            Ultralight::try_with_config(UltralightConfig::default()).unwrap_or_else(|error| {
+                eprintln!("{}", error);
+                std::process::exit(1)
+            });
         Self {
-            icy_browser: IcyBrowser::new().with_tab_bar().with_nav_bar().build(),
+            icy_browser: IcyBrowser::from_engine(engine)
+                .with_tab_bar()
+                .with_nav_bar()
+                .build(),
             custom_widget_state: CustomWidgetState {
                 border_colors: vec![
                     Color::from_rgb(1.0, 0.0, 0.0),   // Red
@@ -62,7 +69,6 @@ impl Browser {
     fn update(&mut self, message: Message) -> Task<Message> {
         match message {
             Message::IcyBrowser(msg) => self.icy_browser.update(msg).map(Message::IcyBrowser),
-            Message::Update => self.icy_browser.force_update().map(Message::IcyBrowser),
             Message::Tick => Task::none(), // Tick
         }
     }
@@ -85,7 +91,8 @@ impl Browser {
 
     fn subscription(&self) -> Subscription<Message> {
         Subscription::batch([
-            time::every(Duration::from_millis(10)).map(move |_| Message::Update),
+            // updates the page and delivers keyboard input to it
+            self.icy_browser.subscription().map(Message::IcyBrowser),
             time::every(Duration::from_millis(16)).map(|_| Message::Tick),
         ])
     }
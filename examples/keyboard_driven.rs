@@ -1,13 +1,12 @@
 // Simple keybaord driven browser using the ultralight(webkit) webengine as a backend
 
-use iced::event::{self, Event};
+use iced::keyboard::key::Named;
 use iced::Theme;
 use iced::{Element, Settings, Subscription, Task};
-use std::time::Duration;
 
 use icy_browser::{
-    get_fonts, widgets, Bookmark, IcyBrowser, KeyType, ShortcutBuilder, ShortcutModifier,
-    Ultralight,
+    get_fonts, Bookmark, IcyBrowser, KeyType, ShortcutBuilder, ShortcutModifier, Ultralight,
+    UltralightConfig,
 };
 
 fn main() -> iced::Result {
@@ -28,8 +27,6 @@ fn main() -> iced::Result {
 #[derive(Debug, Clone)]
 pub enum Message {
     IcyBrowser(icy_browser::Message), // Passes messages to icy_browser
-    Update,
-    Event(Event),
 }
 
 struct Browser {
@@ -53,9 +50,33 @@ impl Default for Browser {
                     KeyType::Key(iced::keyboard::Key::Character("t".into())),
                 ],
             )
-            .build();
-        let widgets = IcyBrowser::new()
+            .add_sequence(
+                icy_browser::Message::CloseCurrentTab,
+                vec![
+                    vec![
+                        KeyType::Modifier(ShortcutModifier::Ctrl),
+                        KeyType::Key(iced::keyboard::Key::Character("k".into())),
+                    ],
+                    vec![
+                        KeyType::Modifier(ShortcutModifier::Ctrl),
+                        KeyType::Key(iced::keyboard::Key::Character("w".into())),
+                    ],
+                ],
+            )
+            .add_shortcut(
+                icy_browser::Message::Refresh,
+                vec![KeyType::named(Named::F5)],
+            )
+            .build()
+            .expect("Invalid shortcuts");
+        let engine =
+            Ultralight::try_with_config(UltralightConfig::default()).unwrap_or_else(|error| {
+                eprintln!("{}", error);
+                std::process::exit(1)
+            });
+        let widgets = IcyBrowser::from_engine(engine)
             .with_custom_shortcuts(shortcuts)
+            .with_modal_input()
             .with_tab_bar()
             .bookmarks(&[
                 Bookmark::new("https://www.rust-lang.org", "rust-lang.org"),
@@ -78,11 +99,6 @@ impl Browser {
     fn update(&mut self, message: Message) -> Task<Message> {
         match message {
             Message::IcyBrowser(msg) => self.icy_browser.update(msg).map(Message::IcyBrowser),
-            Message::Update => self.icy_browser.force_update().map(Message::IcyBrowser),
-            Message::Event(event) => self
-                .icy_browser
-                .update(widgets::Message::IcedEvent(Some(event)))
-                .map(Message::IcyBrowser),
         }
     }
 
@@ -91,10 +107,7 @@ impl Browser {
     }
 
     fn subscription(&self) -> Subscription<Message> {
-        Subscription::batch([
-            iced::time::every(Duration::from_millis(10)).map(move |_| Message::Update),
-            // This is needed for child widgets such as overlay to detect Key events
-            event::listen().map(Message::Event),
-        ])
+        // This is needed for the page and shortcuts to receive Key events
+        self.icy_browser.subscription().map(Message::IcyBrowser)
     }
 }
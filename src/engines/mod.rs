@@ -1,9 +1,9 @@
 use iced::keyboard;
 use iced::mouse::{self, Interaction};
 use iced::Size;
-// use iced::widget::image::{Handle, Image};
-use iced::Point;
+use iced::{Point, Rectangle};
 use rand::Rng;
+use std::sync::Arc;
 use url::Url;
 
 use crate::ImageInfo;
@@ -19,8 +19,13 @@ pub enum PixelFormat {
 #[allow(unused)]
 pub trait BrowserEngine {
     type Info: TabInfo;
+    /// Engine specific settings used by `with_config`
+    type Config: Default;
 
+    /// Panics if the engine can't be created with its default config
     fn new() -> Self;
+    /// Panics if the engine can't be created with `config`
+    fn with_config(config: Self::Config) -> Self;
 
     fn do_work(&self);
     fn need_render(&self) -> bool;
@@ -31,29 +36,166 @@ pub trait BrowserEngine {
     fn pixel_buffer(&mut self) -> (PixelFormat, Vec<u8>);
 
     fn get_cursor(&self) -> Interaction;
-    // fn get_icon(&self) -> Image<Handle>;
     fn goto_url(&self, url: &Url);
     fn goto_html(&self, html: String);
     fn has_loaded(&self) -> bool;
-    fn new_tab(&mut self, url: Url, size: Size<u32>) -> Tab<Self::Info>;
+    /// Creates a tab loading `url` with `user_agent`, or the engine's default when `None`
+    fn new_tab(&mut self, url: Url, size: Size<u32>, user_agent: Option<&str>) -> Tab<Self::Info>;
     fn get_tabs(&self) -> &Tabs<Self::Info>;
     fn get_tabs_mut(&mut self) -> &mut Tabs<Self::Info>;
 
     fn refresh(&self);
+    /// Stops loading the current tab
+    fn stop(&self);
     fn go_forward(&self);
     fn go_back(&self);
     fn focus(&self);
     fn unfocus(&self);
+    /// Whether an editable element, like a text field, has keyboard focus in the current tab
+    fn has_input_focus(&self) -> bool;
+    /// Links, buttons and inputs visible in the current tab
+    fn clickable_elements(&self) -> Vec<ClickableElement>;
+    /// Selects the next match of `query` in the current tab, returns false when there is none
+    fn find_in_page(&self, query: &str, backwards: bool) -> bool;
 
     fn scroll(&self, delta: mouse::ScrollDelta);
+    /// Types the text of key presses, which can be any Unicode, into the current tab
+    ///
+    /// Input method composition isn't supported, iced doesn't report preedit or commit events
     fn handle_keyboard_event(&self, event: keyboard::Event);
     fn handle_mouse_event(&mut self, point: Point, event: mouse::Event);
+    /// The url of the link under the cursor in the current tab
+    fn hovered_link(&self) -> Option<String>;
+    /// Page zoom of the current tab, where 1.0 is 100%
+    fn zoom_level(&self) -> f32;
+    fn set_zoom_level(&self, zoom: f32);
+    /// Drains the new window requests pages have made since the last call
+    fn take_popup_requests(&mut self) -> Vec<PopupRequest>;
+    /// Drains actions requested by pages as tab id and action pairs
+    ///
+    /// Pages request actions by logging `icy_browser:<action>` to the console, any page can
+    /// do so and it's up to the caller to decide which to trust
+    fn take_page_actions(&mut self) -> Vec<(u32, String)>;
+    /// Routes navigations to `scheme` urls through `handler` instead of the network
+    ///
+    /// Engines that can't give the scheme its own origin must keep its pages from local files
+    fn register_scheme_handler(&mut self, scheme: &str, handler: SchemeHandler);
+    fn handles_scheme(&self, scheme: &str) -> bool;
+    /// Sends `user_agent` with the current tab's following loads, or the engine's default when `None`
+    ///
+    /// Engines that only take a user agent when creating a view replace the tab's view, dropping
+    /// its page and history, so call this right before loading a url
+    fn set_user_agent(&mut self, user_agent: Option<&str>);
+    /// Whether site data is only kept in memory or storage deleted with the engine
+    fn is_private(&self) -> bool;
+    /// Cookies readable by scripts of `origin`, e.g. `https://example.com`
+    ///
+    /// Read through the open tabs showing `origin`, so it is empty when none are open
+    fn cookies(&self, origin: &str) -> Vec<Cookie>;
+    /// Expires the cookie `name` of `origin` through an open tab, unless it is `HttpOnly`
+    fn delete_cookie(&self, origin: &str, name: &str);
+    /// Removes the script readable cookies, local storage and session storage of `origin`
+    ///
+    /// Done through an open tab, so `HttpOnly` cookies, cookies the page can't see, such as
+    /// ones for other paths, and the disk cache are kept. `Profile::clear_engine_data_on_start`
+    /// removes those
+    fn clear_site_data(&self, origin: &str);
+    /// Drops resources the engine has cached in memory, the disk cache is kept
+    fn clear_memory_cache(&self);
+}
+
+/// An element that can be clicked, positioned in the pixels of the tab's view
+#[derive(Clone, Debug, PartialEq)]
+pub struct ClickableElement {
+    pub bounds: Rectangle,
+    /// The link target, if the element is a link
+    pub url: Option<String>,
+}
+
+/// A cookie as seen by scripts on the page
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Cookie {
+    pub name: String,
+    pub value: String,
+}
+
+/// Serves a custom url scheme, returning the response body and its mime type
+pub type SchemeHandler = Arc<dyn Fn(&Url) -> Option<(Vec<u8>, String)> + Send + Sync>;
+
+/// Console messages with this prefix are treated as page actions
+pub const PAGE_ACTION_PREFIX: &str = "icy_browser:";
+
+/// Sent to sites that serve a reduced page to unknown or mobile browsers
+pub const DESKTOP_USER_AGENT: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) \
+    AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36";
+
+/// Sent to sites to get their mobile layout
+pub const MOBILE_USER_AGENT: &str = "Mozilla/5.0 (iPhone; CPU iPhone OS 17_0 like Mac OS X) \
+    AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.0 Mobile/15E148 Safari/604.1";
+
+/// A page asking for a new window, from `window.open` or a link with `target=_blank`
+#[derive(Clone, Debug, PartialEq)]
+pub struct PopupRequest {
+    /// Id of the tab that made the request
+    pub opener: u32,
+    pub url: String,
+    /// True when the page asked for a popup window rather than a new tab
+    pub is_popup: bool,
 }
 
 /// Engine specific tab information
 pub trait TabInfo {
     fn url(&self) -> String;
     fn title(&self) -> String;
+    /// Tooltip text the page is showing, e.g. from a `title` attribute
+    fn tooltip(&self) -> Option<String>;
+    /// The url of the link under the cursor
+    fn hovered_link(&self) -> Option<String>;
+    fn load_state(&self) -> LoadState;
+    /// How many main frame loads have started in this tab, including ones the page started itself
+    fn load_count(&self) -> u32;
+    /// The icon the page declares with `<link rel=icon>`
+    fn icon_url(&self) -> Option<String>;
+    /// Takes the error from the last failed main frame load, if any
+    fn take_load_error(&self) -> Option<LoadError>;
+    /// The user agent this tab was given with `new_tab` or `set_user_agent`, `None` when using the default
+    fn user_agent(&self) -> Option<String>;
+}
+
+/// Why a page failed to load, as reported by the engine
+#[derive(Clone, Debug, PartialEq)]
+pub struct LoadError {
+    pub url: String,
+    pub code: i32,
+    /// The engine's error domain, e.g. `NSURLErrorDomain`
+    pub domain: String,
+    pub description: String,
+}
+
+/// How far along a tab's main frame is in loading
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum LoadState {
+    /// Progress from 0.0 to 1.0
+    Loading(f32),
+    #[default]
+    Loaded,
+}
+
+impl LoadState {
+    // Engines report milestones rather than bytes, so progress is estimated from them
+    pub const STARTED: LoadState = LoadState::Loading(0.1);
+    pub const DOM_READY: LoadState = LoadState::Loading(0.7);
+
+    pub fn is_loading(&self) -> bool {
+        matches!(self, LoadState::Loading(_))
+    }
+
+    pub fn progress(&self) -> Option<f32> {
+        match self {
+            LoadState::Loading(progress) => Some(*progress),
+            LoadState::Loaded => None,
+        }
+    }
 }
 
 /// Can be converted from Tab to hold information for ResultType
@@ -62,6 +204,7 @@ pub struct DisplayTab {
     pub id: u32,
     pub url: String,
     pub title: String,
+    pub opener: Option<u32>,
 }
 
 impl<Info: TabInfo> From<Tab<Info>> for DisplayTab {
@@ -70,6 +213,7 @@ impl<Info: TabInfo> From<Tab<Info>> for DisplayTab {
             id: tab.id,
             url: tab.url(),
             title: tab.title(),
+            opener: tab.opener,
         }
     }
 }
@@ -77,6 +221,8 @@ impl<Info: TabInfo> From<Tab<Info>> for DisplayTab {
 /// Stores Tab info like url & title
 pub struct Tab<Info: TabInfo> {
     id: u32,
+    /// The tab this one was spawned from, if any
+    opener: Option<u32>,
     view: ImageInfo,
     info: Info,
 }
@@ -86,6 +232,7 @@ impl<Info: TabInfo> Tab<Info> {
         let id = rand::thread_rng().gen();
         Self {
             id,
+            opener: None,
             view: ImageInfo::default(),
             info,
         }
@@ -96,6 +243,7 @@ impl<Info: TabInfo> Tab<Info> {
             id: self.id,
             url: self.url(),
             title: self.title(),
+            opener: self.opener,
         }
     }
 
@@ -111,6 +259,10 @@ impl<Info: TabInfo> Tab<Info> {
         self.id
     }
 
+    pub fn opener(&self) -> Option<u32> {
+        self.opener
+    }
+
     pub fn url(&self) -> String {
         self.info.url()
     }
@@ -118,6 +270,30 @@ impl<Info: TabInfo> Tab<Info> {
     pub fn title(&self) -> String {
         self.info.title()
     }
+
+    pub fn tooltip(&self) -> Option<String> {
+        self.info.tooltip()
+    }
+
+    pub fn hovered_link(&self) -> Option<String> {
+        self.info.hovered_link()
+    }
+
+    pub fn load_state(&self) -> LoadState {
+        self.info.load_state()
+    }
+
+    pub fn icon_url(&self) -> Option<String> {
+        self.info.icon_url()
+    }
+
+    pub fn take_load_error(&self) -> Option<LoadError> {
+        self.info.take_load_error()
+    }
+
+    pub fn user_agent(&self) -> Option<String> {
+        self.info.user_agent()
+    }
 }
 
 pub struct Tabs<Info: TabInfo> {
@@ -166,6 +342,10 @@ impl<Info: TabInfo> Tabs<Info> {
         self.history.push(id)
     }
 
+    pub fn contains(&self, id: u32) -> bool {
+        self.tabs.iter().any(|tab| tab.id == id)
+    }
+
     pub fn tabs(&self) -> &Vec<Tab<Info>> {
         &self.tabs
     }
@@ -180,11 +360,80 @@ impl<Info: TabInfo> Tabs<Info> {
         id
     }
 
+    /// Inserts a tab spawned from `opener`, after the opener and any tabs it already opened
+    pub fn insert_with_opener(&mut self, mut tab: Tab<Info>, opener: u32) -> u32 {
+        let id = tab.id;
+        tab.opener = Some(opener);
+
+        let mut index = self.id_to_index(opener) + 1;
+        while index < self.tabs.len() && self.is_descendant(self.tabs[index].id, opener) {
+            index += 1;
+        }
+        self.tabs.insert(index, tab);
+        id
+    }
+
+    /// Returns true if `id` was opened by `ancestor`, directly or through other tabs
+    pub fn is_descendant(&self, id: u32, ancestor: u32) -> bool {
+        let mut opener = self.get(id).opener;
+        while let Some(opener_id) = opener {
+            if opener_id == ancestor {
+                return true;
+            }
+            opener = self.get(opener_id).opener;
+        }
+        false
+    }
+
+    /// Returns the ids of the tabs directly opened by `id`, in tab order
+    pub fn children(&self, id: u32) -> Vec<u32> {
+        self.tabs
+            .iter()
+            .filter(|tab| tab.opener == Some(id))
+            .map(|tab| tab.id)
+            .collect()
+    }
+
     /// Returns the newly active tab
+    ///
+    /// Closing the active tab focuses the nearest sibling sharing its opener,
+    /// then the opener itself, and otherwise the previously active tab
     pub fn remove(&mut self, id: u32) -> u32 {
-        self.history.retain(|tab_id| *tab_id != id);
+        let index = self.id_to_index(id);
+        let opener = self.tabs[index].opener;
+
+        let next = if self.history.last() == Some(&id) {
+            opener.map(|opener| {
+                let siblings: Vec<(usize, u32)> = self
+                    .tabs
+                    .iter()
+                    .enumerate()
+                    .filter(|(idx, tab)| *idx != index && tab.opener == Some(opener))
+                    .map(|(idx, tab)| (idx, tab.id))
+                    .collect();
+                siblings
+                    .iter()
+                    .find(|(idx, _)| *idx > index)
+                    .or(siblings.last())
+                    .map(|(_, sibling)| *sibling)
+                    .unwrap_or(opener)
+            })
+        } else {
+            None
+        };
+
+        // children are adopted by the closed tab's opener to keep the tree intact
+        for tab in self.tabs.iter_mut() {
+            if tab.opener == Some(id) {
+                tab.opener = opener;
+            }
+        }
 
+        self.history.retain(|tab_id| *tab_id != id);
         self.tabs.retain(|tab| tab.id != id);
+        if let Some(next) = next {
+            self.set_current_id(next);
+        }
         self.get_current_id()
     }
 
@@ -214,3 +463,133 @@ impl<Info: TabInfo> Tabs<Info> {
         panic!("Unable to find Tab with id: {}", id);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct TestInfo;
+
+    impl TabInfo for TestInfo {
+        fn url(&self) -> String {
+            String::new()
+        }
+        fn title(&self) -> String {
+            String::new()
+        }
+        fn tooltip(&self) -> Option<String> {
+            None
+        }
+        fn hovered_link(&self) -> Option<String> {
+            None
+        }
+        fn load_state(&self) -> LoadState {
+            LoadState::Loaded
+        }
+        fn load_count(&self) -> u32 {
+            0
+        }
+        fn icon_url(&self) -> Option<String> {
+            None
+        }
+        fn take_load_error(&self) -> Option<LoadError> {
+            None
+        }
+        fn user_agent(&self) -> Option<String> {
+            None
+        }
+    }
+
+    fn open(tabs: &mut Tabs<TestInfo>, opener: Option<u32>) -> u32 {
+        let id = match opener {
+            Some(opener) => tabs.insert_with_opener(Tab::new(TestInfo), opener),
+            None => tabs.insert(Tab::new(TestInfo)),
+        };
+        tabs.set_current_id(id);
+        id
+    }
+
+    fn order(tabs: &Tabs<TestInfo>) -> Vec<u32> {
+        tabs.tabs().iter().map(|tab| tab.id()).collect()
+    }
+
+    #[test]
+    fn opened_tabs_follow_their_opener_and_its_other_children() {
+        let mut tabs = Tabs::new();
+        let parent = open(&mut tabs, None);
+        let other = open(&mut tabs, None);
+        let first = open(&mut tabs, Some(parent));
+        let grandchild = open(&mut tabs, Some(first));
+        let second = open(&mut tabs, Some(parent));
+
+        assert_eq!(order(&tabs), vec![parent, first, grandchild, second, other]);
+        assert!(tabs.is_descendant(grandchild, parent));
+        assert_eq!(tabs.children(parent), vec![first, second]);
+    }
+
+    #[test]
+    fn closing_a_child_focuses_the_next_sibling() {
+        let mut tabs = Tabs::new();
+        let parent = open(&mut tabs, None);
+        let first = open(&mut tabs, Some(parent));
+        let second = open(&mut tabs, Some(parent));
+        tabs.set_current_id(first);
+
+        assert_eq!(tabs.remove(first), second);
+    }
+
+    #[test]
+    fn closing_the_last_child_focuses_the_previous_sibling() {
+        let mut tabs = Tabs::new();
+        let parent = open(&mut tabs, None);
+        let first = open(&mut tabs, Some(parent));
+        let second = open(&mut tabs, Some(parent));
+
+        assert_eq!(tabs.remove(second), first);
+    }
+
+    #[test]
+    fn closing_an_only_child_focuses_the_opener() {
+        let mut tabs = Tabs::new();
+        let parent = open(&mut tabs, None);
+        open(&mut tabs, None);
+        let child = open(&mut tabs, Some(parent));
+
+        assert_eq!(tabs.remove(child), parent);
+    }
+
+    #[test]
+    fn closing_a_tab_without_opener_focuses_the_previous_tab() {
+        let mut tabs = Tabs::new();
+        let first = open(&mut tabs, None);
+        open(&mut tabs, None);
+        let last = open(&mut tabs, None);
+        tabs.set_current_id(first);
+        tabs.set_current_id(last);
+
+        assert_eq!(tabs.remove(last), first);
+    }
+
+    #[test]
+    fn closing_a_background_tab_keeps_the_current_tab() {
+        let mut tabs = Tabs::new();
+        let parent = open(&mut tabs, None);
+        let first = open(&mut tabs, Some(parent));
+        let second = open(&mut tabs, Some(parent));
+        tabs.set_current_id(parent);
+
+        assert_eq!(tabs.remove(second), parent);
+        assert_eq!(order(&tabs), vec![parent, first]);
+    }
+
+    #[test]
+    fn children_of_a_closed_tab_are_adopted_by_its_opener() {
+        let mut tabs = Tabs::new();
+        let parent = open(&mut tabs, None);
+        let child = open(&mut tabs, Some(parent));
+        let grandchild = open(&mut tabs, Some(child));
+
+        tabs.remove(child);
+        assert_eq!(tabs.get(grandchild).opener(), Some(parent));
+    }
+}
@@ -1,9 +1,13 @@
 use clipboard_rs::{Clipboard, ClipboardContext};
 use iced::keyboard::{self};
 use iced::mouse::{self, ScrollDelta};
-use iced::{Point, Size};
+use iced::{Point, Rectangle, Size};
 use smol_str::SmolStr;
-use std::sync::{Arc, RwLock};
+use std::collections::HashMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock, Weak};
 use ul_next::{
     config::Config,
     event::{self, KeyEventCreationInfo, MouseEvent, ScrollEvent},
@@ -14,9 +18,14 @@ use ul_next::{
     window::Cursor,
     Surface,
 };
-use url::Url;
+use url::{Position, Url};
 
-use super::{BrowserEngine, PixelFormat, Tab, TabInfo, Tabs};
+use crate::Profile;
+
+use super::{
+    BrowserEngine, ClickableElement, Cookie, LoadError, LoadState, PixelFormat, PopupRequest,
+    SchemeHandler, Tab, TabInfo, Tabs, PAGE_ACTION_PREFIX,
+};
 
 struct UlClipboard;
 impl platform::Clipboard for UlClipboard {
@@ -34,10 +43,585 @@ impl platform::Clipboard for UlClipboard {
     }
 }
 
+type SchemeHandlers = Arc<RwLock<HashMap<String, SchemeHandler>>>;
+// whether each view shows a custom scheme page, dropped along with the view's tab
+type SchemePages = Arc<RwLock<Vec<Weak<AtomicBool>>>>;
+
+// Ultralight only asks the filesystem for `file` urls, so custom schemes are loaded from here.
+// Their pages share the `file` origin, so local files are refused while a tab shows one
+const SCHEME_DIR: &str = "__scheme__";
+
+/// Maps `app://dashboard/page?tab=1` to `file:///__scheme__/app/dashboard/page%3Ftab=1`
+///
+/// The query is kept in the path as the filesystem is only given paths
+fn scheme_to_file_url(url: &Url) -> String {
+    let rest = url[Position::AfterScheme..Position::AfterQuery]
+        .trim_start_matches(':')
+        .trim_start_matches('/')
+        .replace('?', "%3F");
+    let fragment = url
+        .fragment()
+        .map(|fragment| format!("#{}", fragment))
+        .unwrap_or_default();
+    format!(
+        "file:///{}/{}/{}{}",
+        SCHEME_DIR,
+        url.scheme(),
+        rest,
+        fragment
+    )
+}
+
+/// Maps a path under `SCHEME_DIR` back to the custom scheme url it stands for
+fn scheme_path_to_url(path: &str) -> Option<Url> {
+    let rest = path.trim_start_matches('/').strip_prefix(SCHEME_DIR)?;
+    let (scheme, rest) = rest.strip_prefix('/')?.split_once('/')?;
+    let rest = rest.replace("%3F", "?").replace("%3f", "?");
+    Url::parse(&format!("{}://{}", scheme, rest)).ok()
+}
+
+fn is_scheme_file_url(url: &str) -> bool {
+    url.strip_prefix("file://")
+        .and_then(scheme_path_to_url)
+        .is_some()
+}
+
+// A cookie is only replaced by one with the same path and domain, which scripts can't read,
+// so every path above the page and every parent domain is tried
+const EXPIRE_COOKIES: &str = "function expireCookies(names) { \
+    let paths = ['/']; \
+    location.pathname.split('/').filter(Boolean).reduce(function(path, part) { \
+        path += '/' + part; \
+        paths.push(path); \
+        return path; \
+    }, ''); \
+    let parts = location.hostname.split('.'); \
+    let domains = ['']; \
+    for (let i = 0; i < parts.length - 1; i++) { \
+        domains.push('; domain=' + parts.slice(i).join('.')); \
+    } \
+    names.filter(Boolean).forEach(function(name) { \
+        paths.forEach(function(path) { \
+            domains.forEach(function(domain) { \
+                document.cookie = name + '=; expires=Thu, 01 Jan 1970 00:00:00 GMT; path=' + path + domain; \
+            }); \
+        }); \
+    }); \
+}";
+
+// Console messages with this prefix report the link under the cursor, see `WATCH_LINKS`
+const HOVER_PREFIX: &str = "icy_browser_hover:";
+
+// Reports the hovered link with `HOVER_PREFIX` whenever the cursor moves onto another element,
+// so nothing has to ask the page on every mouse move
+const WATCH_LINKS: &str = "(function() { \
+    let hovered = ''; \
+    document.addEventListener('mouseover', function(event) { \
+        let link = event.target.closest ? event.target.closest('a[href]') : null; \
+        let href = link ? link.href : ''; \
+        if (href !== hovered) { \
+            hovered = href; \
+            console.log('icy_browser_hover:' + href); \
+        } \
+    }, true); \
+    return ''; \
+})()";
+
+/// The runtime files Ultralight needs from its sdk
+const RESOURCES: [&str; 2] = ["cacert.pem", "icudt67l.dat"];
+
+/// Checked for a resources directory when none is configured
+pub const RESOURCES_ENV_VAR: &str = "ICY_BROWSER_ULTRALIGHT_RESOURCES";
+
+#[derive(Debug)]
+pub enum UltralightError {
+    /// A resources directory was given but a file is missing from it
+    MissingResource { dir: PathBuf, file: &'static str },
+    /// No resources directory was configured or embedded
+    ResourcesNotFound,
+    /// Ultralight refused to create its config, renderer or a view
+    Creation(String),
+}
+
+impl fmt::Display for UltralightError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UltralightError::MissingResource { dir, file } => write!(
+                f,
+                "Ultralight resource {} is missing from {}",
+                file,
+                dir.display()
+            ),
+            UltralightError::ResourcesNotFound => write!(
+                f,
+                "Ultralight resources (cacert.pem, icudt67l.dat) were not found, set \
+                UltralightConfig::resources_dir or the {} environment variable, \
+                or build with it set and the ultralight-resources feature to embed them",
+                RESOURCES_ENV_VAR
+            ),
+            UltralightError::Creation(error) => write!(f, "Failed to create Ultralight: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for UltralightError {}
+
+/// Configures how `Ultralight` is created
+#[derive(Clone, Debug)]
+pub struct UltralightConfig {
+    resources_dir: Option<PathBuf>,
+    file_system: VirtualFileSystem,
+    cache_path: Option<PathBuf>,
+    user_agent: Option<String>,
+    font_standard: String,
+    font_fixed: Option<String>,
+    font_serif: Option<String>,
+    font_sans_serif: Option<String>,
+    font_gamma: Option<f64>,
+    device_scale: f64,
+    scroll_step: f32,
+    scroll_timer_delay: Option<f64>,
+    transparent: bool,
+    javascript: bool,
+    memory_cache_size: Option<u32>,
+    page_cache_size: Option<u32>,
+    ram_size: Option<u32>,
+    private: bool,
+    // kept alive with the engine so a temporary profile outlives every view using it
+    profile: Option<Profile>,
+}
+
+impl Default for UltralightConfig {
+    fn default() -> Self {
+        Self {
+            resources_dir: None,
+            file_system: VirtualFileSystem::default(),
+            cache_path: None,
+            user_agent: None,
+            font_standard: String::from("Arial"),
+            font_fixed: None,
+            font_serif: None,
+            font_sans_serif: None,
+            font_gamma: None,
+            device_scale: 1.0,
+            scroll_step: 100.0,
+            scroll_timer_delay: None,
+            transparent: false,
+            javascript: true,
+            memory_cache_size: None,
+            page_cache_size: None,
+            ram_size: None,
+            private: false,
+            profile: None,
+        }
+    }
+}
+
+impl UltralightConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The directory holding Ultralight's `cacert.pem` and `icudt67l.dat`
+    ///
+    /// Otherwise `RESOURCES_ENV_VAR` and then the resources embedded by the
+    /// `ultralight-resources` feature are used
+    pub fn resources_dir(mut self, path: impl Into<PathBuf>) -> Self {
+        self.resources_dir = Some(path.into());
+        self
+    }
+
+    /// Limits local file access to `files`
+    pub fn file_system(mut self, files: VirtualFileSystem) -> Self {
+        self.file_system = files;
+        self
+    }
+
+    /// Where Ultralight keeps its disk cache, otherwise nothing is cached on disk
+    pub fn cache_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.cache_path = Some(path.into());
+        self
+    }
+
+    /// Keeps cookies, local storage and the disk cache in `profile`
+    ///
+    /// A clear scheduled with `Profile::clear_engine_data_on_start` runs when the engine is created
+    pub fn profile(mut self, profile: &Profile) -> Self {
+        self.cache_path = Some(profile.engine_dir());
+        self.profile = Some(profile.clone());
+        self
+    }
+
+    /// Keeps all site data in a temporary profile that is deleted with the engine
+    ///
+    /// Overrides `profile` and `cache_path`
+    pub fn private(mut self) -> Self {
+        self.private = true;
+        self
+    }
+
+    /// Replaces Ultralight's default user agent for every tab
+    pub fn user_agent(mut self, user_agent: &str) -> Self {
+        self.user_agent = Some(user_agent.to_string());
+        self
+    }
+
+    /// The font used when a page doesn't specify one, defaults to Arial
+    pub fn font_standard(mut self, family: &str) -> Self {
+        self.font_standard = family.to_string();
+        self
+    }
+
+    pub fn font_fixed(mut self, family: &str) -> Self {
+        self.font_fixed = Some(family.to_string());
+        self
+    }
+
+    pub fn font_serif(mut self, family: &str) -> Self {
+        self.font_serif = Some(family.to_string());
+        self
+    }
+
+    pub fn font_sans_serif(mut self, family: &str) -> Self {
+        self.font_sans_serif = Some(family.to_string());
+        self
+    }
+
+    /// Gamma used when rendering font glyphs, Ultralight defaults to 1.8
+    pub fn font_gamma(mut self, gamma: f64) -> Self {
+        self.font_gamma = Some(gamma);
+        self
+    }
+
+    /// Scale of new views, e.g. 2.0 for high dpi displays
+    ///
+    /// Zoom levels are relative to it, so 100% is always this scale
+    pub fn device_scale(mut self, scale: f64) -> Self {
+        self.device_scale = scale;
+        self
+    }
+
+    /// Pixels scrolled per line of a mouse wheel, defaults to 100
+    pub fn scroll_step(mut self, pixels: f32) -> Self {
+        self.scroll_step = pixels;
+        self
+    }
+
+    /// Seconds between steps of animated scrolling
+    pub fn scroll_timer_delay(mut self, seconds: f64) -> Self {
+        self.scroll_timer_delay = Some(seconds);
+        self
+    }
+
+    /// Leaves the page background transparent instead of white
+    pub fn transparent(mut self, transparent: bool) -> Self {
+        self.transparent = transparent;
+        self
+    }
+
+    pub fn javascript(mut self, enabled: bool) -> Self {
+        self.javascript = enabled;
+        self
+    }
+
+    /// Bytes of memory used to cache resources
+    pub fn memory_cache_size(mut self, bytes: u32) -> Self {
+        self.memory_cache_size = Some(bytes);
+        self
+    }
+
+    /// Number of pages kept in memory for back and forward navigation
+    pub fn page_cache_size(mut self, pages: u32) -> Self {
+        self.page_cache_size = Some(pages);
+        self
+    }
+
+    /// Overrides the amount of ram, in megabytes, Ultralight sizes its caches from
+    pub fn ram_size(mut self, megabytes: u32) -> Self {
+        self.ram_size = Some(megabytes);
+        self
+    }
+
+    fn build_config(&self) -> Result<Config, UltralightError> {
+        let mut config = Config::start();
+        if let Some(path) = &self.cache_path {
+            config = config.cache_path(&path.to_string_lossy());
+        }
+        if let Some(gamma) = self.font_gamma {
+            config = config.font_gamma(gamma);
+        }
+        if let Some(delay) = self.scroll_timer_delay {
+            config = config.scroll_timer_delay(delay);
+        }
+        if let Some(bytes) = self.memory_cache_size {
+            config = config.memory_cache_size(bytes);
+        }
+        if let Some(pages) = self.page_cache_size {
+            config = config.page_cache_size(pages);
+        }
+        if let Some(megabytes) = self.ram_size {
+            config = config.override_ram_size(megabytes);
+        }
+        config
+            .build()
+            .map_err(|error| UltralightError::Creation(format!("{:?}", error)))
+    }
+
+    fn build_view_config(&self) -> Result<ViewConfig, UltralightError> {
+        let mut view_config = ViewConfig::start()
+            .initial_device_scale(self.device_scale)
+            .font_family_standard(&self.font_standard)
+            .is_transparent(self.transparent)
+            .enable_javascript(self.javascript)
+            .is_accelerated(false);
+        if let Some(family) = &self.font_fixed {
+            view_config = view_config.font_family_fixed(family);
+        }
+        if let Some(family) = &self.font_serif {
+            view_config = view_config.font_family_serif(family);
+        }
+        if let Some(family) = &self.font_sans_serif {
+            view_config = view_config.font_family_sans_serif(family);
+        }
+        if let Some(user_agent) = &self.user_agent {
+            view_config = view_config.user_agent(user_agent);
+        }
+        view_config
+            .build()
+            .map_err(|error| UltralightError::Creation(format!("{:?}", error)))
+    }
+
+    /// Points the file system at the resources, or embeds them
+    fn resolve_resources(&self) -> Result<VirtualFileSystem, UltralightError> {
+        let mut files = self.file_system.clone();
+        let dir = self
+            .resources_dir
+            .clone()
+            .or_else(|| std::env::var_os(RESOURCES_ENV_VAR).map(PathBuf::from));
+
+        if let Some(dir) = dir {
+            if let Some(file) = RESOURCES.iter().find(|file| !dir.join(file).is_file()) {
+                return Err(UltralightError::MissingResource { dir, file });
+            }
+            files.resources_dir = Some(dir);
+            return Ok(files);
+        }
+
+        embed_resources(files).ok_or(UltralightError::ResourcesNotFound)
+    }
+}
+
+/// Embeds the resources the build script copied from the sdk
+#[cfg(ultralight_resources_embedded)]
+fn embed_resources(files: VirtualFileSystem) -> Option<VirtualFileSystem> {
+    Some(
+        files
+            .embed(
+                "resources/cacert.pem",
+                include_bytes!(concat!(env!("OUT_DIR"), "/ul-resources/cacert.pem")),
+            )
+            .embed(
+                "resources/icudt67l.dat",
+                include_bytes!(concat!(env!("OUT_DIR"), "/ul-resources/icudt67l.dat")),
+            ),
+    )
+}
+
+#[cfg(not(ultralight_resources_embedded))]
+fn embed_resources(_files: VirtualFileSystem) -> Option<VirtualFileSystem> {
+    None
+}
+
+/// Where Ultralight may read local files from
+///
+/// Paths are resolved against embedded assets first, then `resources/` against the resources
+/// directory, then each asset root in order. Other local files are refused unless allowed with
+/// `allow_local_files`.
+#[derive(Clone, Debug)]
+pub struct VirtualFileSystem {
+    resources_dir: Option<PathBuf>,
+    asset_roots: Vec<PathBuf>,
+    embedded: HashMap<String, &'static [u8]>,
+    // the absolute directory local files may be read from
+    local_root: Option<PathBuf>,
+}
+
+impl Default for VirtualFileSystem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl VirtualFileSystem {
+    pub fn new() -> Self {
+        Self {
+            resources_dir: None,
+            asset_roots: Vec::new(),
+            embedded: HashMap::new(),
+            local_root: None,
+        }
+    }
+
+    /// Serves files under `path` as if they were in the working directory
+    pub fn asset_root(mut self, path: impl Into<PathBuf>) -> Self {
+        self.asset_roots.push(path.into());
+        self
+    }
+
+    /// Serves `bytes` at `path`, e.g. from `include_bytes!`
+    pub fn embed(mut self, path: &str, bytes: &'static [u8]) -> Self {
+        self.embedded
+            .insert(path.trim_start_matches('/').to_string(), bytes);
+        self
+    }
+
+    /// Lets pages read local files under `root`, which panics unless `root` is absolute
+    ///
+    /// Local files are refused by default, `/` allows every file the process can read
+    pub fn allow_local_files(mut self, root: impl Into<PathBuf>) -> Self {
+        let root = root.into();
+        assert!(
+            root.is_absolute(),
+            "local files need an absolute root, got {}",
+            root.display()
+        );
+        self.local_root = Some(root);
+        self
+    }
+
+    /// Finds the file for `path`, only looking outside the configured files if `allow_local`
+    fn resolve(&self, path: &str, allow_local: bool) -> Option<FileSource> {
+        let relative = path.trim_start_matches('/');
+        if let Some(bytes) = self.embedded.get(relative) {
+            return Some(FileSource::Embedded(*bytes));
+        }
+
+        let resource = relative
+            .strip_prefix("resources/")
+            .and_then(|resource| contained(self.resources_dir.as_ref()?, resource));
+        let asset = || {
+            self.asset_roots
+                .iter()
+                .find_map(|root| contained(root, relative))
+        };
+        let local = || {
+            let root = self.local_root.as_ref().filter(|_| allow_local)?;
+            // file urls only carry absolute paths, so they are checked from the filesystem root
+            let file = Path::new("/").join(relative).canonicalize().ok()?;
+            (file.starts_with(root.canonicalize().ok()?) && file.is_file()).then_some(file)
+        };
+        resource.or_else(asset).or_else(local).map(FileSource::File)
+    }
+}
+
+/// Joins `path` onto `root` only if the file exists and stays inside `root`
+fn contained(root: &Path, path: &str) -> Option<PathBuf> {
+    let root = root.canonicalize().ok()?;
+    let file = root.join(path).canonicalize().ok()?;
+    (file.starts_with(&root) && file.is_file()).then_some(file)
+}
+
+enum FileSource {
+    Embedded(&'static [u8]),
+    File(PathBuf),
+}
+
+/// Serves custom schemes and otherwise reads files through a `VirtualFileSystem`
+struct UlFileSystem {
+    files: VirtualFileSystem,
+    handlers: SchemeHandlers,
+    scheme_pages: SchemePages,
+    // the last response is kept between the exists, mime type and open calls for its path
+    response: Option<(String, (Vec<u8>, String))>,
+}
+
+impl UlFileSystem {
+    fn scheme_response(&mut self, path: &str) -> Option<&(Vec<u8>, String)> {
+        if self.response.as_ref().map(|(cached, _)| cached.as_str()) != Some(path) {
+            // replacing the cached response also drops one that was never opened
+            self.response = None;
+            let url = scheme_path_to_url(path)?;
+            let handler = self.handlers.read().unwrap().get(url.scheme())?.clone();
+            self.response = Some((path.to_string(), handler(&url)?));
+        }
+        self.response.as_ref().map(|(_, response)| response)
+    }
+
+    fn resolve(&self, path: &str) -> Option<FileSource> {
+        let scheme_page_open = self.scheme_pages.read().unwrap().iter().any(|page| {
+            page.upgrade()
+                .is_some_and(|page| page.load(Ordering::Relaxed))
+        });
+        self.files.resolve(path, !scheme_page_open)
+    }
+}
+
+impl platform::FileSystem for UlFileSystem {
+    fn file_exists(&mut self, path: &str) -> bool {
+        if scheme_path_to_url(path).is_some() {
+            self.scheme_response(path).is_some()
+        } else {
+            self.resolve(path).is_some()
+        }
+    }
+
+    fn get_file_mime_type(&mut self, path: &str) -> String {
+        if scheme_path_to_url(path).is_some() {
+            if let Some((_, mime_type)) = self.scheme_response(path) {
+                return mime_type.clone();
+            }
+        }
+        mime_type(Path::new(path)).to_string()
+    }
+
+    fn get_file_charset(&mut self, _path: &str) -> String {
+        String::from("utf-8")
+    }
+
+    fn open_file(&mut self, path: &str) -> Option<Vec<u8>> {
+        if scheme_path_to_url(path).is_some() {
+            self.scheme_response(path)?;
+            return self.response.take().map(|(_, (data, _))| data);
+        }
+        match self.resolve(path)? {
+            FileSource::Embedded(bytes) => Some(bytes.to_vec()),
+            FileSource::File(file) => std::fs::read(file).ok(),
+        }
+    }
+}
+
+fn mime_type(path: &Path) -> &'static str {
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("html" | "htm") => "text/html",
+        Some("css") => "text/css",
+        Some("js" | "mjs") => "application/javascript",
+        Some("json") => "application/json",
+        Some("svg") => "image/svg+xml",
+        Some("png") => "image/png",
+        Some("jpg" | "jpeg") => "image/jpeg",
+        Some("gif") => "image/gif",
+        Some("webp") => "image/webp",
+        Some("ico") => "image/x-icon",
+        Some("woff") => "font/woff",
+        Some("woff2") => "font/woff2",
+        Some("ttf") => "font/ttf",
+        Some("txt") => "text/plain",
+        _ => "application/octet-stream",
+    }
+}
+
 pub struct UltalightTabInfo {
     surface: Surface,
     view: View,
     cursor: Arc<RwLock<mouse::Interaction>>,
+    tooltip: Arc<RwLock<String>>,
+    hovered_link: Arc<RwLock<Option<String>>>,
+    load_state: Arc<RwLock<LoadState>>,
+    load_count: Arc<RwLock<u32>>,
+    load_error: Arc<RwLock<Option<LoadError>>>,
+    // only owned here, it is set on each main frame load and watched through `SchemePages`
+    _on_scheme_page: Arc<AtomicBool>,
+    user_agent: Option<String>,
 }
 
 impl TabInfo for UltalightTabInfo {
@@ -46,14 +630,63 @@ impl TabInfo for UltalightTabInfo {
     }
 
     fn url(&self) -> String {
-        self.view.url().expect("Failed to get url from ultralight")
+        let url = self.view.url().expect("Failed to get url from ultralight");
+        // show custom scheme pages under their own url rather than the file they load from
+        url.strip_prefix("file://")
+            .and_then(scheme_path_to_url)
+            .map(|url| url.to_string())
+            .unwrap_or(url)
+    }
+
+    fn tooltip(&self) -> Option<String> {
+        let tooltip = self.tooltip.read().unwrap();
+        (!tooltip.is_empty()).then(|| tooltip.clone())
+    }
+
+    fn hovered_link(&self) -> Option<String> {
+        self.hovered_link.read().unwrap().clone()
+    }
+
+    fn load_state(&self) -> LoadState {
+        *self.load_state.read().unwrap()
+    }
+
+    fn load_count(&self) -> u32 {
+        *self.load_count.read().unwrap()
+    }
+
+    fn icon_url(&self) -> Option<String> {
+        let script = "(function() { \
+            let link = document.querySelector(\"link[rel~='icon']\"); \
+            return link ? link.href : ''; \
+        })()";
+        match self.view.evaluate_script(script) {
+            Ok(Ok(href)) if !href.is_empty() => Some(href),
+            _ => None,
+        }
+    }
+
+    fn take_load_error(&self) -> Option<LoadError> {
+        self.load_error.write().unwrap().take()
+    }
+
+    fn user_agent(&self) -> Option<String> {
+        self.user_agent.clone()
     }
 }
 
 pub struct Ultralight {
     renderer: Renderer,
     view_config: ViewConfig,
+    // kept to build view configs for tabs with their own user agent
+    config: UltralightConfig,
     tabs: Tabs<UltalightTabInfo>,
+    popups: Arc<RwLock<Vec<PopupRequest>>>,
+    page_actions: Arc<RwLock<Vec<(u32, String)>>>,
+    scheme_handlers: SchemeHandlers,
+    scheme_pages: SchemePages,
+    // pixels per scrolled line
+    scroll_step: f32,
 }
 
 impl Default for Ultralight {
@@ -63,24 +696,238 @@ impl Default for Ultralight {
 }
 
 impl Ultralight {
+    /// Panics when Ultralight's resources can't be found, see `try_with_config`
     pub fn new() -> Self {
-        let config = Config::start().build().unwrap();
+        <Self as BrowserEngine>::with_config(UltralightConfig::default())
+    }
+
+    pub fn try_with_config(mut config: UltralightConfig) -> Result<Self, UltralightError> {
+        if config.private {
+            let profile = Profile::temporary()
+                .map_err(|error| UltralightError::Creation(error.to_string()))?;
+            config = config.profile(&profile);
+        }
+        if let Some(profile) = &config.profile {
+            profile
+                .run_scheduled_clear()
+                .map_err(|error| UltralightError::Creation(error.to_string()))?;
+        }
+        let files = config.resolve_resources()?;
+        let view_config = config.build_view_config()?;
+        let scroll_step = config.scroll_step;
+        let ul_config = config.build_config()?;
+        let scheme_handlers = SchemeHandlers::default();
+        let scheme_pages = SchemePages::default();
         platform::enable_platform_fontloader();
-        platform::enable_platform_filesystem(".").unwrap();
+        platform::set_filesystem(UlFileSystem {
+            files,
+            handlers: scheme_handlers.clone(),
+            scheme_pages: scheme_pages.clone(),
+            response: None,
+        });
         platform::set_clipboard(UlClipboard);
 
-        let renderer = Renderer::create(config).unwrap();
-        let view_config = ViewConfig::start()
-            .initial_device_scale(1.0)
-            .font_family_standard("Arial")
-            .is_accelerated(false)
-            .build()
-            .unwrap();
+        let renderer = Renderer::create(ul_config)
+            .map_err(|error| UltralightError::Creation(format!("{:?}", error)))?;
 
-        Self {
+        Ok(Self {
             renderer,
             view_config,
+            config,
             tabs: Tabs::new(),
+            popups: Arc::new(RwLock::new(Vec::new())),
+            page_actions: Arc::new(RwLock::new(Vec::new())),
+            scheme_handlers,
+            scheme_pages,
+            scroll_step,
+        })
+    }
+
+    /// Creates a view loading `url` with the callbacks that don't need the tab id
+    fn create_tab_info(
+        &self,
+        url: &str,
+        size: Size<u32>,
+        user_agent: Option<String>,
+    ) -> UltalightTabInfo {
+        let view = match &user_agent {
+            Some(user_agent) => {
+                let view_config = self
+                    .config
+                    .clone()
+                    .user_agent(user_agent)
+                    .build_view_config()
+                    .unwrap();
+                self.renderer
+                    .create_view(size.width, size.height, &view_config, None)
+            }
+            None => self
+                .renderer
+                .create_view(size.width, size.height, &self.view_config, None),
+        }
+        .unwrap();
+
+        let surface = view.surface().unwrap();
+        view.load_url(url).unwrap();
+
+        // RGBA
+        debug_assert!(surface.row_bytes() / size.width == 4);
+
+        let cursor = Arc::new(RwLock::new(mouse::Interaction::Idle));
+        let cb_cursor = cursor.clone();
+        view.set_change_cursor_callback(move |_view, cursor_update| {
+            *cb_cursor.write().unwrap() = match cursor_update {
+                Cursor::None => mouse::Interaction::Idle,
+                Cursor::Pointer => mouse::Interaction::Idle,
+                Cursor::Hand => mouse::Interaction::Pointer,
+                Cursor::Grab => mouse::Interaction::Grab,
+                Cursor::VerticalText => mouse::Interaction::Text,
+                Cursor::IBeam => mouse::Interaction::Text,
+                Cursor::Cross => mouse::Interaction::Crosshair,
+                Cursor::Wait => mouse::Interaction::Working,
+                Cursor::Grabbing => mouse::Interaction::Grab,
+                Cursor::NorthSouthResize => mouse::Interaction::ResizingVertically,
+                Cursor::EastWestResize => mouse::Interaction::ResizingHorizontally,
+                Cursor::NotAllowed => mouse::Interaction::NotAllowed,
+                Cursor::ZoomIn => mouse::Interaction::ZoomIn,
+                Cursor::ZoomOut => mouse::Interaction::ZoomIn,
+                _ => mouse::Interaction::Pointer,
+            };
+        });
+
+        let tooltip = Arc::new(RwLock::new(String::new()));
+        let cb_tooltip = tooltip.clone();
+        view.set_change_tooltip_callback(move |_view, tooltip_update| {
+            *cb_tooltip.write().unwrap() = tooltip_update;
+        });
+
+        let on_scheme_page = Arc::new(AtomicBool::new(is_scheme_file_url(url)));
+        let mut scheme_pages = self.scheme_pages.write().unwrap();
+        scheme_pages.retain(|page| page.strong_count() > 0);
+        scheme_pages.push(Arc::downgrade(&on_scheme_page));
+        drop(scheme_pages);
+
+        let hovered_link = Arc::new(RwLock::new(None));
+        let load_state = Arc::new(RwLock::new(LoadState::STARTED));
+        let load_count = Arc::new(RwLock::new(0));
+        let cb_load_state = load_state.clone();
+        let cb_load_count = load_count.clone();
+        let cb_hovered_link = hovered_link.clone();
+        let cb_on_scheme_page = on_scheme_page.clone();
+        view.set_begin_loading_callback(move |_view, _frame_id, is_main_frame, url| {
+            if is_main_frame {
+                *cb_load_state.write().unwrap() = LoadState::STARTED;
+                *cb_load_count.write().unwrap() += 1;
+                *cb_hovered_link.write().unwrap() = None;
+                cb_on_scheme_page.store(is_scheme_file_url(&url), Ordering::Relaxed);
+            }
+        });
+        let cb_load_state = load_state.clone();
+        view.set_dom_ready_callback(move |view, _frame_id, is_main_frame, _url| {
+            if is_main_frame {
+                *cb_load_state.write().unwrap() = LoadState::DOM_READY;
+                let _ = view.evaluate_script(WATCH_LINKS);
+            }
+        });
+        let cb_load_state = load_state.clone();
+        view.set_finish_loading_callback(move |_view, _frame_id, is_main_frame, _url| {
+            if is_main_frame {
+                *cb_load_state.write().unwrap() = LoadState::Loaded;
+            }
+        });
+
+        let load_error = Arc::new(RwLock::new(None));
+        let cb_load_error = load_error.clone();
+        let cb_load_state = load_state.clone();
+        view.set_fail_loading_callback(
+            move |_view, _frame_id, is_main_frame, url, description, domain, code| {
+                if is_main_frame {
+                    *cb_load_state.write().unwrap() = LoadState::Loaded;
+                    *cb_load_error.write().unwrap() = Some(LoadError {
+                        url,
+                        code,
+                        domain,
+                        description,
+                    });
+                }
+            },
+        );
+
+        UltalightTabInfo {
+            surface,
+            view,
+            cursor,
+            tooltip,
+            hovered_link,
+            load_state,
+            load_count,
+            load_error,
+            _on_scheme_page: on_scheme_page,
+            user_agent,
+        }
+    }
+
+    /// Registers the callbacks that report back with the id of the tab owning `info`
+    fn watch_tab(&self, id: u32, info: &UltalightTabInfo) {
+        let view = &info.view;
+        // child views are never created here, the request is queued so it can become a `Tab`
+        let cb_popups = self.popups.clone();
+        view.set_create_child_view_callback(
+            move |_view, _opener_url, target_url, is_popup, _popup_rect| {
+                cb_popups.write().unwrap().push(PopupRequest {
+                    opener: id,
+                    url: target_url,
+                    is_popup,
+                });
+                None
+            },
+        );
+
+        let cb_page_actions = self.page_actions.clone();
+        let cb_hovered_link = info.hovered_link.clone();
+        view.set_add_console_message_callback(
+            move |_view, _source, _level, message, _line, _column, _source_id| {
+                if let Some(href) = message.strip_prefix(HOVER_PREFIX) {
+                    *cb_hovered_link.write().unwrap() =
+                        (!href.is_empty()).then(|| href.to_string());
+                } else if let Some(action) = message.strip_prefix(PAGE_ACTION_PREFIX) {
+                    cb_page_actions
+                        .write()
+                        .unwrap()
+                        .push((id, action.to_string()));
+                }
+            },
+        );
+    }
+
+    /// Views of the open tabs showing `origin`
+    fn views_at<'a>(&'a self, origin: &'a str) -> impl Iterator<Item = &'a View> {
+        self.tabs
+            .tabs()
+            .iter()
+            .filter(move |tab| {
+                Url::parse(&tab.url())
+                    .map(|url| url.origin().ascii_serialization() == origin.trim_end_matches('/'))
+                    .unwrap_or(false)
+            })
+            .map(|tab| &tab.info.view)
+    }
+
+    /// Runs `script` in one tab showing `origin`, storage is shared so one is enough
+    fn evaluate_at(&self, origin: &str, script: &str) -> Option<String> {
+        let view = self.views_at(origin).next()?;
+        match view.evaluate_script(script) {
+            Ok(Ok(result)) => Some(result),
+            _ => None,
+        }
+    }
+
+    /// The url Ultralight should load, routing registered schemes through the filesystem
+    fn resolve_url(&self, url: &Url) -> String {
+        if self.handles_scheme(url.scheme()) {
+            scheme_to_file_url(url)
+        } else {
+            url.to_string()
         }
     }
 }
@@ -88,10 +935,16 @@ impl Ultralight {
 impl BrowserEngine for Ultralight {
     type Info = UltalightTabInfo;
 
+    type Config = UltralightConfig;
+
     fn new() -> Self {
         Ultralight::new()
     }
 
+    fn with_config(config: UltralightConfig) -> Self {
+        Self::try_with_config(config).unwrap_or_else(|error| panic!("{}", error))
+    }
+
     fn do_work(&self) {
         self.renderer.update()
     }
@@ -153,7 +1006,7 @@ impl BrowserEngine for Ultralight {
             .get_current()
             .info
             .view
-            .load_url(url.as_ref())
+            .load_url(&self.resolve_url(url))
             .unwrap();
     }
 
@@ -169,53 +1022,28 @@ impl BrowserEngine for Ultralight {
         &mut self.tabs
     }
 
-    fn new_tab(&mut self, url: Url, size: Size<u32>) -> Tab<UltalightTabInfo> {
-        let view = self
-            .renderer
-            .create_view(size.width, size.height, &self.view_config, None)
-            .unwrap();
-
-        let surface = view.surface().unwrap();
-        view.load_url(url.as_ref()).unwrap();
-
-        // RGBA
-        debug_assert!(surface.row_bytes() / size.width == 4);
-
-        let cursor = Arc::new(RwLock::new(mouse::Interaction::Idle));
-        let cb_cursor = cursor.clone();
-        view.set_change_cursor_callback(move |_view, cursor_update| {
-            *cb_cursor.write().unwrap() = match cursor_update {
-                Cursor::None => mouse::Interaction::Idle,
-                Cursor::Pointer => mouse::Interaction::Idle,
-                Cursor::Hand => mouse::Interaction::Pointer,
-                Cursor::Grab => mouse::Interaction::Grab,
-                Cursor::VerticalText => mouse::Interaction::Text,
-                Cursor::IBeam => mouse::Interaction::Text,
-                Cursor::Cross => mouse::Interaction::Crosshair,
-                Cursor::Wait => mouse::Interaction::Working,
-                Cursor::Grabbing => mouse::Interaction::Grab,
-                Cursor::NorthSouthResize => mouse::Interaction::ResizingVertically,
-                Cursor::EastWestResize => mouse::Interaction::ResizingHorizontally,
-                Cursor::NotAllowed => mouse::Interaction::NotAllowed,
-                Cursor::ZoomIn => mouse::Interaction::ZoomIn,
-                Cursor::ZoomOut => mouse::Interaction::ZoomIn,
-                _ => mouse::Interaction::Pointer,
-            };
-        });
-
-        let info = UltalightTabInfo {
-            surface,
-            view,
-            cursor,
-        };
-
-        Tab::new(info)
+    fn new_tab(
+        &mut self,
+        url: Url,
+        size: Size<u32>,
+        user_agent: Option<&str>,
+    ) -> Tab<UltalightTabInfo> {
+        let info =
+            self.create_tab_info(&self.resolve_url(&url), size, user_agent.map(String::from));
+        let tab = Tab::new(info);
+        self.watch_tab(tab.id(), &tab.info);
+        tab
     }
 
     fn refresh(&self) {
         self.tabs.get_current().info.view.reload();
     }
 
+    fn stop(&self) {
+        self.tabs.get_current().info.view.stop();
+        *self.tabs.get_current().info.load_state.write().unwrap() = LoadState::Loaded;
+    }
+
     fn go_forward(&self) {
         self.tabs.get_current().info.view.go_forward();
     }
@@ -232,12 +1060,67 @@ impl BrowserEngine for Ultralight {
         self.tabs.get_current().info.view.unfocus();
     }
 
+    fn has_input_focus(&self) -> bool {
+        self.tabs.get_current().info.view.has_input_focus()
+    }
+
+    fn find_in_page(&self, query: &str, backwards: bool) -> bool {
+        // a json string is also a valid javascript string
+        let query = serde_json::to_string(query).unwrap_or_default();
+        let script = format!(
+            "window.find({}, false, {}, true) ? 'true' : 'false'",
+            query, backwards
+        );
+        let view = &self.tabs.get_current().info.view;
+        matches!(view.evaluate_script(&script), Ok(Ok(result)) if result == "true")
+    }
+
+    fn clickable_elements(&self) -> Vec<ClickableElement> {
+        let view = &self.tabs.get_current().info.view;
+        let script = "(function() { \
+            let selector = 'a[href], button, input:not([type=hidden]), select, textarea, \
+                summary, [onclick], [role=button], [role=link], [contenteditable=true]'; \
+            let elements = []; \
+            document.querySelectorAll(selector).forEach(function(element) { \
+                let rect = element.getBoundingClientRect(); \
+                if (rect.width > 0 && rect.height > 0 && rect.bottom > 0 && rect.right > 0 \
+                    && rect.top < window.innerHeight && rect.left < window.innerWidth) { \
+                    elements.push([rect.left, rect.top, rect.width, rect.height, element.href || '']); \
+                } \
+            }); \
+            return JSON.stringify(elements); \
+        })()";
+        let Ok(Ok(json)) = view.evaluate_script(script) else {
+            return Vec::new();
+        };
+        let Ok(serde_json::Value::Array(elements)) = serde_json::from_str(&json) else {
+            return Vec::new();
+        };
+
+        // the page measures in css pixels
+        let scale = view.device_scale() as f32;
+        elements
+            .iter()
+            .filter_map(|element| {
+                let number = |index: usize| Some(element.get(index)?.as_f64()? as f32 * scale);
+                let url = element.get(4)?.as_str()?;
+                Some(ClickableElement {
+                    bounds: Rectangle::new(
+                        Point::new(number(0)?, number(1)?),
+                        Size::new(number(2)?, number(3)?),
+                    ),
+                    url: (!url.is_empty()).then(|| url.to_string()),
+                })
+            })
+            .collect()
+    }
+
     fn scroll(&self, delta: ScrollDelta) {
         let scroll_event = match delta {
             ScrollDelta::Lines { x, y } => ScrollEvent::new(
                 ul_next::event::ScrollEventType::ScrollByPixel,
-                x as i32 * 100,
-                y as i32 * 100,
+                (x * self.scroll_step) as i32,
+                (y * self.scroll_step) as i32,
             )
             .unwrap(),
             ScrollDelta::Pixels { x, y } => ScrollEvent::new(
@@ -297,8 +1180,29 @@ impl BrowserEngine for Ultralight {
         match event {
             mouse::Event::ButtonPressed(mouse::Button::Other(_)) => (),
             mouse::Event::ButtonReleased(mouse::Button::Other(_)) => (),
-            mouse::Event::ButtonPressed(mouse::Button::Middle) => (),
-            mouse::Event::ButtonReleased(mouse::Button::Middle) => (),
+            mouse::Event::ButtonPressed(mouse::Button::Middle) => {
+                self.tabs.get_current().info.view.fire_mouse_event(
+                    MouseEvent::new(
+                        ul_next::event::MouseEventType::MouseDown,
+                        point.x as i32,
+                        point.y as i32,
+                        ul_next::event::MouseButton::Middle,
+                    )
+                    .unwrap(),
+                );
+            }
+            mouse::Event::ButtonReleased(mouse::Button::Middle) => {
+                self.tabs.get_current().info.view.fire_mouse_event(
+                    MouseEvent::new(
+                        ul_next::event::MouseEventType::MouseUp,
+                        point.x as i32,
+                        point.y as i32,
+                        ul_next::event::MouseButton::Middle,
+                    )
+                    .unwrap(),
+                );
+            }
+            // history navigation is handled by `IcyBrowser`
             mouse::Event::ButtonPressed(mouse::Button::Forward) => (),
             mouse::Event::ButtonReleased(mouse::Button::Forward) => (),
             mouse::Event::ButtonPressed(mouse::Button::Back) => (),
@@ -360,6 +1264,7 @@ impl BrowserEngine for Ultralight {
             }
             mouse::Event::WheelScrolled { delta } => self.scroll(delta),
             mouse::Event::CursorLeft => {
+                *self.tabs.get_current().info.hovered_link.write().unwrap() = None;
                 self.unfocus();
             }
             mouse::Event::CursorEntered => {
@@ -367,6 +1272,112 @@ impl BrowserEngine for Ultralight {
             }
         }
     }
+
+    fn hovered_link(&self) -> Option<String> {
+        self.tabs.get_current().hovered_link()
+    }
+
+    // zoom scales the configured device scale, so 100% keeps a high dpi scale
+    fn zoom_level(&self) -> f32 {
+        (self.tabs.get_current().info.view.device_scale() / self.config.device_scale) as f32
+    }
+
+    fn set_zoom_level(&self, zoom: f32) {
+        self.tabs
+            .get_current()
+            .info
+            .view
+            .set_device_scale(self.config.device_scale * zoom as f64);
+    }
+
+    fn take_popup_requests(&mut self) -> Vec<PopupRequest> {
+        std::mem::take(&mut *self.popups.write().unwrap())
+    }
+
+    fn take_page_actions(&mut self) -> Vec<(u32, String)> {
+        std::mem::take(&mut *self.page_actions.write().unwrap())
+    }
+
+    fn set_user_agent(&mut self, user_agent: Option<&str>) {
+        let current = self.tabs.get_current();
+        let view = &current.info.view;
+        let size = Size::new(view.width(), view.height());
+        let zoom = view.device_scale();
+        let id = current.id();
+
+        // views take their user agent on creation, the caller loads the page into the new one
+        let info = self.create_tab_info("about:blank", size, user_agent.map(String::from));
+        info.view.set_device_scale(zoom);
+        self.watch_tab(id, &info);
+        info.view.focus();
+        self.tabs.get_current_mut().info = info;
+    }
+
+    fn is_private(&self) -> bool {
+        match &self.config.profile {
+            Some(profile) => profile.is_temporary(),
+            None => self.config.cache_path.is_none(),
+        }
+    }
+
+    fn cookies(&self, origin: &str) -> Vec<Cookie> {
+        let Some(cookies) = self.evaluate_at(origin, "document.cookie") else {
+            return Vec::new();
+        };
+        cookies
+            .split(';')
+            .filter_map(|cookie| {
+                let (name, value) = cookie.trim().split_once('=')?;
+                Some(Cookie {
+                    name: name.to_string(),
+                    value: value.to_string(),
+                })
+            })
+            .collect()
+    }
+
+    fn delete_cookie(&self, origin: &str, name: &str) {
+        let name = name.replace('\\', "\\\\").replace('\'', "\\'");
+        let script = format!(
+            "(function() {{ {} expireCookies(['{}']); return ''; }})()",
+            EXPIRE_COOKIES, name
+        );
+        self.evaluate_at(origin, &script);
+    }
+
+    fn clear_site_data(&self, origin: &str) {
+        let script = format!(
+            "(function() {{ \
+                {} \
+                expireCookies(document.cookie.split(';').map(function(cookie) {{ \
+                    return cookie.split('=')[0].trim(); \
+                }})); \
+                localStorage.clear(); \
+                sessionStorage.clear(); \
+                return ''; \
+            }})()",
+            EXPIRE_COOKIES
+        );
+        self.evaluate_at(origin, &script);
+    }
+
+    fn clear_memory_cache(&self) {
+        self.renderer.purge_memory();
+    }
+
+    fn register_scheme_handler(&mut self, scheme: &str, handler: SchemeHandler) {
+        self.scheme_handlers
+            .write()
+            .unwrap()
+            .insert(scheme.to_lowercase(), handler);
+    }
+
+    fn handles_scheme(&self, scheme: &str) -> bool {
+        self.scheme_handlers
+            .read()
+            .unwrap()
+            .contains_key(&scheme.to_lowercase())
+    }
 }
 
 #[derive(Debug, PartialEq, Eq)]
@@ -375,6 +1386,27 @@ enum KeyPress {
     Unpress,
 }
 
+/// The text a key press types, `None` for an unidentified key that types nothing
+fn key_text(key: &keyboard::Key, text: Option<SmolStr>) -> Option<String> {
+    match key {
+        keyboard::Key::Named(keyboard::key::Named::Space) => Some(String::from(" ")),
+        keyboard::Key::Named(_) => Some(String::new()),
+        keyboard::Key::Character(_) => Some(text.map(|text| text.to_string()).unwrap_or_default()),
+        // text composed without a known key, e.g. after a dead key
+        keyboard::Key::Unidentified => text.map(|text| text.to_string()),
+    }
+}
+
+/// Whether a key press is sent as a `Char` event that types `text`, which can be any Unicode
+fn types_text(press: &KeyPress, text: &str, modifiers: &keyboard::Modifiers) -> bool {
+    // AltGr is reported as ctrl and alt, and types text like `@` or `€`
+    let ctrl_shortcut = modifiers.control() && !modifiers.alt();
+    *press == KeyPress::Press
+        && !ctrl_shortcut
+        && !text.is_empty()
+        && !text.chars().any(char::is_control)
+}
+
 fn iced_key_to_ultralight_key(
     press: KeyPress,
     modified_key: Option<keyboard::Key>,
@@ -385,20 +1417,7 @@ fn iced_key_to_ultralight_key(
 ) -> Option<event::KeyEvent> {
     let (text, virtual_key, native_key) = {
         if let Some(key) = key {
-            let text = match key {
-                keyboard::Key::Named(key) => {
-                    if key == keyboard::key::Named::Space {
-                        String::from(" ")
-                    } else {
-                        String::from("")
-                    }
-                }
-                keyboard::Key::Character(_) => match text {
-                    Some(text) => text.to_string(),
-                    None => String::from(""),
-                },
-                keyboard::Key::Unidentified => return None,
-            };
+            let text = key_text(&key, text)?;
             let (virtual_key, native_key) = match key {
                 keyboard::Key::Named(key) => match key {
                     keyboard::key::Named::Control => (
@@ -957,9 +1976,10 @@ fn iced_key_to_ultralight_key(
                         #[cfg(unix)]
                         27,
                     ),
-                    _ => return None,
+                    // other characters, like `é` or `ж`, are typed through their text
+                    _ => (VirtualKeyCode::Unknown, 0),
                 },
-                keyboard::Key::Unidentified => return None,
+                keyboard::Key::Unidentified => (VirtualKeyCode::Unknown, 0),
             };
             (text, virtual_key, native_key)
         } else {
@@ -967,6 +1987,7 @@ fn iced_key_to_ultralight_key(
         }
     };
 
+    let types_text = types_text(&press, &text, &modifiers);
     let modifiers = event::KeyEventModifiers {
         alt: modifiers.alt(),
         ctrl: modifiers.control(),
@@ -974,9 +1995,9 @@ fn iced_key_to_ultralight_key(
         shift: modifiers.shift(),
     };
 
-    let ty = if modifiers.ctrl {
+    let ty = if modifiers.ctrl && !modifiers.alt {
         event::KeyEventType::RawKeyDown
-    } else if !text.is_empty() && text.is_ascii() && press == KeyPress::Press {
+    } else if types_text {
         event::KeyEventType::Char
     } else {
         match press {
@@ -1003,3 +2024,125 @@ fn iced_key_to_ultralight_key(
 
     event::KeyEvent::new(creation_info).ok()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn scheme_urls_round_trip_through_file_urls() {
+        let url = Url::parse("app://dashboard/page?tab=1&q=a%20b#top").unwrap();
+        let file_url = scheme_to_file_url(&url);
+        assert_eq!(
+            file_url,
+            "file:///__scheme__/app/dashboard/page%3Ftab=1&q=a%20b#top"
+        );
+
+        let path = file_url
+            .strip_prefix("file://")
+            .unwrap()
+            .split('#')
+            .next()
+            .unwrap();
+        assert_eq!(
+            scheme_path_to_url(path).unwrap().as_str(),
+            "app://dashboard/page?tab=1&q=a%20b"
+        );
+        assert_eq!(
+            scheme_path_to_url("/__scheme__/app/dashboard/page?tab=1")
+                .unwrap()
+                .as_str(),
+            "app://dashboard/page?tab=1"
+        );
+    }
+
+    #[test]
+    fn only_paths_under_the_scheme_dir_are_scheme_urls() {
+        assert!(scheme_path_to_url("/etc/passwd").is_none());
+        assert!(scheme_path_to_url("/__scheme__").is_none());
+        assert!(scheme_path_to_url("/__schemes__/app/page").is_none());
+        assert!(is_scheme_file_url("file:///__scheme__/app/page"));
+        assert!(!is_scheme_file_url("file:///home/page.html"));
+    }
+
+    fn character(text: &str) -> (keyboard::Key, Option<SmolStr>) {
+        (keyboard::Key::Character(text.into()), Some(text.into()))
+    }
+
+    #[test]
+    fn keys_type_any_unicode_text() {
+        for typed in ["é", "ж", "中", "🦀"] {
+            let (key, text) = character(typed);
+            let text = key_text(&key, text).unwrap();
+            assert_eq!(text, typed);
+            assert!(types_text(
+                &KeyPress::Press,
+                &text,
+                &keyboard::Modifiers::empty()
+            ));
+            assert!(!types_text(
+                &KeyPress::Unpress,
+                &text,
+                &keyboard::Modifiers::empty()
+            ));
+        }
+    }
+
+    #[test]
+    fn dead_keys_type_their_composed_text() {
+        assert_eq!(
+            key_text(&keyboard::Key::Unidentified, Some("ü".into())).as_deref(),
+            Some("ü")
+        );
+        assert_eq!(key_text(&keyboard::Key::Unidentified, None), None);
+    }
+
+    #[test]
+    fn alt_gr_types_text_but_ctrl_shortcuts_do_not() {
+        let alt_gr = keyboard::Modifiers::CTRL | keyboard::Modifiers::ALT;
+        assert!(types_text(&KeyPress::Press, "€", &alt_gr));
+        assert!(!types_text(
+            &KeyPress::Press,
+            "w",
+            &keyboard::Modifiers::CTRL
+        ));
+    }
+
+    #[test]
+    fn local_files_are_only_read_under_an_allowed_root() {
+        let dir = tempfile::tempdir().unwrap();
+        let allowed = dir.path().join("site");
+        fs::create_dir(&allowed).unwrap();
+        fs::write(allowed.join("page.html"), "page").unwrap();
+        fs::write(dir.path().join("secret.txt"), "secret").unwrap();
+        let path = |file: &str| dir.path().join(file).to_str().unwrap().to_string();
+
+        let files = VirtualFileSystem::new();
+        assert!(files.resolve(&path("site/page.html"), true).is_none());
+
+        let files = files.allow_local_files(&allowed);
+        assert!(files.resolve(&path("site/page.html"), true).is_some());
+        assert!(files.resolve(&path("site/page.html"), false).is_none());
+        assert!(files.resolve(&path("secret.txt"), true).is_none());
+        assert!(files.resolve(&path("site/../secret.txt"), true).is_none());
+    }
+
+    #[test]
+    #[should_panic]
+    fn local_files_need_an_absolute_root() {
+        let _ = VirtualFileSystem::new().allow_local_files("site");
+    }
+
+    #[test]
+    fn named_keys_type_nothing_but_space() {
+        let named = |key| key_text(&keyboard::Key::Named(key), None).unwrap();
+        assert_eq!(named(keyboard::key::Named::Space), " ");
+        assert_eq!(named(keyboard::key::Named::Enter), "");
+        assert!(!types_text(
+            &KeyPress::Press,
+            "\r",
+            &keyboard::Modifiers::empty()
+        ));
+    }
+}
@@ -0,0 +1,223 @@
+use iced::widget::image::Handle;
+use image::{DynamicImage, ImageOutputFormat};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io::Cursor;
+use std::path::PathBuf;
+use std::time::Duration;
+use url::Url;
+
+/// Icons larger than this are not downloaded
+const MAX_FAVICON_BYTES: usize = 1024 * 1024;
+const FETCH_TIMEOUT: Duration = Duration::from_secs(10);
+/// The size icons are shrunk to for html pages
+const DATA_URL_SIZE: u32 = 32;
+
+struct Icon {
+    handle: Handle,
+    // a small png for html pages, which can't use the handle
+    data_url: String,
+}
+
+/// Fetches, decodes and caches site icons by origin
+///
+/// The default only keeps icons in memory, `IcyBrowser::with_profile` caches them in the profile
+#[derive(Default)]
+pub struct Favicons {
+    icons: HashMap<String, Icon>,
+    // origins that have been fetched or are being fetched
+    requested: HashSet<String>,
+    /// When `None` icons are only kept in memory
+    cache_dir: Option<PathBuf>,
+}
+
+impl Favicons {
+    pub fn new(cache_dir: Option<PathBuf>) -> Self {
+        Self {
+            icons: HashMap::new(),
+            requested: HashSet::new(),
+            cache_dir,
+        }
+    }
+
+    /// Returns the icon for the origin of `url` if it has been loaded
+    pub fn get(&self, url: &str) -> Option<Handle> {
+        self.icons
+            .get(&origin(url)?)
+            .map(|icon| icon.handle.clone())
+    }
+
+    /// Returns the icon for the origin of `url` as a `data:` url for html pages
+    pub fn data_url(&self, url: &str) -> Option<&str> {
+        self.icons
+            .get(&origin(url)?)
+            .map(|icon| icon.data_url.as_str())
+    }
+
+    /// Returns true the first time an origin is seen, loading it from the disk cache if present
+    ///
+    /// When this returns true the icon should be fetched and passed to `insert`
+    pub fn request(&mut self, url: &str) -> bool {
+        let Some(origin) = origin(url) else {
+            return false;
+        };
+        if !self.requested.insert(origin.clone()) {
+            return false;
+        }
+
+        if let Some(path) = self.cache_path(&origin) {
+            if let Ok(bytes) = fs::read(path) {
+                if let Some(icon) = decode(&bytes) {
+                    self.icons.insert(origin, icon);
+                    return false;
+                }
+            }
+        }
+        true
+    }
+
+    /// Decodes and stores a fetched icon for the origin of `url`
+    pub fn insert(&mut self, url: &str, bytes: &[u8]) {
+        let (Some(origin), Some(icon)) = (origin(url), decode(bytes)) else {
+            return;
+        };
+
+        if let Some(path) = self.cache_path(&origin) {
+            if let Some(dir) = path.parent() {
+                let _ = fs::create_dir_all(dir);
+            }
+            let _ = fs::write(path, bytes);
+        }
+        self.icons.insert(origin, icon);
+    }
+
+    /// Forgets every icon, removing them from the disk cache too
+    pub fn clear(&mut self) {
+        self.icons.clear();
+        self.requested.clear();
+        if let Some(dir) = &self.cache_dir {
+            let _ = fs::remove_dir_all(dir);
+        }
+    }
+
+    fn cache_path(&self, origin: &str) -> Option<PathBuf> {
+        let name: String = origin
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect();
+        Some(self.cache_dir.as_ref()?.join(name))
+    }
+}
+
+/// The icon declared by the page, otherwise the site's `/favicon.ico`
+pub fn favicon_url(page_url: &str, declared: Option<String>) -> Option<Url> {
+    let page_url = Url::parse(page_url).ok()?;
+    if !matches!(page_url.scheme(), "http" | "https") {
+        return None;
+    }
+    declared
+        .and_then(|declared| page_url.join(&declared).ok())
+        .or_else(|| page_url.join("/favicon.ico").ok())
+}
+
+/// Downloads an icon, giving up on slow servers and on anything over `MAX_FAVICON_BYTES`
+pub async fn fetch_favicon(url: Url) -> Option<Vec<u8>> {
+    let client = reqwest::Client::builder()
+        .timeout(FETCH_TIMEOUT)
+        .build()
+        .ok()?;
+    let mut response = client.get(url).send().await.ok()?.error_for_status().ok()?;
+    if response
+        .content_length()
+        .is_some_and(|length| length > MAX_FAVICON_BYTES as u64)
+    {
+        return None;
+    }
+
+    // the declared length can't be trusted, so the body is counted as it arrives
+    let mut bytes = Vec::new();
+    while let Some(chunk) = response.chunk().await.ok()? {
+        if bytes.len() + chunk.len() > MAX_FAVICON_BYTES {
+            return None;
+        }
+        bytes.extend_from_slice(&chunk);
+    }
+    Some(bytes)
+}
+
+fn origin(url: &str) -> Option<String> {
+    let url = Url::parse(url).ok()?;
+    matches!(url.scheme(), "http" | "https").then(|| url.origin().ascii_serialization())
+}
+
+fn decode(bytes: &[u8]) -> Option<Icon> {
+    let icon = image::load_from_memory(bytes).ok()?;
+
+    let small = if icon.width() > DATA_URL_SIZE || icon.height() > DATA_URL_SIZE {
+        icon.thumbnail(DATA_URL_SIZE, DATA_URL_SIZE)
+    } else {
+        icon.clone()
+    };
+    let mut png = Vec::new();
+    DynamicImage::ImageRgba8(small.into_rgba8())
+        .write_to(&mut Cursor::new(&mut png), ImageOutputFormat::Png)
+        .ok()?;
+
+    let icon = icon.into_rgba8();
+    Some(Icon {
+        handle: Handle::from_rgba(icon.width(), icon.height(), icon.into_raw()),
+        data_url: format!("data:image/png;base64,{}", base64(&png)),
+    })
+}
+
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let triple = chunk.iter().enumerate().fold(0u32, |triple, (i, byte)| {
+            triple | ((*byte as u32) << (16 - 8 * i))
+        });
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(ALPHABET[((triple >> (18 - 6 * i)) & 0x3f) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn base64_pads_partial_chunks() {
+        assert_eq!(base64(b""), "");
+        assert_eq!(base64(b"f"), "Zg==");
+        assert_eq!(base64(b"fo"), "Zm8=");
+        assert_eq!(base64(b"foo"), "Zm9v");
+        assert_eq!(base64(b"foobar"), "Zm9vYmFy");
+    }
+
+    #[test]
+    fn icons_are_kept_by_origin_with_a_data_url() {
+        let mut png = Vec::new();
+        DynamicImage::new_rgba8(64, 64)
+            .write_to(&mut Cursor::new(&mut png), ImageOutputFormat::Png)
+            .unwrap();
+
+        let mut favicons = Favicons::default();
+        assert!(favicons.request("https://example.com/a"));
+        assert!(!favicons.request("https://example.com/b"));
+        favicons.insert("https://example.com/a", &png);
+
+        assert!(favicons.get("https://example.com/c").is_some());
+        assert!(favicons
+            .data_url("https://example.com/")
+            .is_some_and(|url| url.starts_with("data:image/png;base64,")));
+        assert!(favicons.get("https://other.com/").is_none());
+    }
+}
@@ -1,10 +1,11 @@
 use iced::widget::{
-    button,
+    button, image,
     image::{Handle, Image},
-    Button,
+    row, text, Button,
 };
+use iced::Alignment;
 pub use iced_fonts::BOOTSTRAP_FONT_BYTES;
-use std::{borrow::Cow, fmt, str::FromStr};
+use std::{borrow::Cow, fmt, str::FromStr, time::SystemTime};
 use url::{ParseError, Url};
 
 use super::{Message, PixelFormat};
@@ -86,7 +87,6 @@ pub type Bookmarks = Vec<Bookmark>;
 pub struct Bookmark {
     url: Url,
     name: String,
-    // icon: Optional<>
 }
 
 impl Bookmark {
@@ -97,8 +97,15 @@ impl Bookmark {
         }
     }
 
-    pub fn as_button(&self) -> Button<Message> {
-        button(self.name.as_str()).on_press(Message::GoToUrl(self.url.to_string()))
+    /// Site icons are cached by origin in `Favicons` rather than stored on the bookmark
+    pub fn as_button(&self, icon: Option<Handle>) -> Button<Message> {
+        let label = match icon {
+            Some(icon) => row![image(icon).width(16).height(16), text(self.name.as_str())]
+                .spacing(5)
+                .align_y(Alignment::Center),
+            None => row![text(self.name.as_str())],
+        };
+        button(label).on_press(Message::GoToUrl(self.url.to_string()))
     }
 
     pub fn url(&self) -> &Url {
@@ -115,3 +122,11 @@ impl fmt::Display for Bookmark {
         write!(f, "{}", self.name)
     }
 }
+
+/// A page visit recorded by `IcyBrowser`
+#[derive(Debug, Clone, PartialEq)]
+pub struct HistoryEntry {
+    pub url: String,
+    pub title: String,
+    pub visited: SystemTime,
+}
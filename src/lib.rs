@@ -2,22 +2,41 @@ pub use iced_fonts::BOOTSTRAP_FONT_BYTES;
 pub use iced_on_focus_widget::hoverable;
 
 mod engines;
-pub use engines::{BrowserEngine, PixelFormat, Tab, TabInfo, Tabs};
+pub use engines::{
+    BrowserEngine, ClickableElement, Cookie, LoadError, LoadState, PixelFormat, PopupRequest,
+    SchemeHandler, Tab, TabInfo, Tabs, DESKTOP_USER_AGENT, MOBILE_USER_AGENT,
+};
 
 #[cfg(feature = "ultralight")]
-pub use engines::ultralight::Ultralight;
+pub use engines::ultralight::{
+    Ultralight, UltralightConfig, UltralightError, VirtualFileSystem, RESOURCES_ENV_VAR,
+};
 
 pub mod widgets;
 pub use widgets::{
-    browser_view, command_palette, nav_bar, tab_bar, HomepageType, IcyBrowser, Message,
+    browser_view, command_palette, nav_bar, shortcut_editor, shortcut_help, status_bar, tab_bar,
+    BrowsingData, FocusTarget, HomepageType, IcyBrowser, InputMode, Message, PopupPolicy,
+    ShortcutEditorState,
 };
 
 mod helpers;
-pub use helpers::{get_fonts, to_url, Bookmark, Bookmarks, ImageInfo};
+pub use helpers::{get_fonts, to_url, Bookmark, Bookmarks, HistoryEntry, ImageInfo};
+
+mod favicon;
+pub use favicon::Favicons;
+
+mod pages;
+pub use pages::{ABOUT_PAGES, DEFAULT_ERROR_PAGE};
+
+mod profile;
+pub use profile::Profile;
 
 mod shortcut;
 pub use shortcut::{
-    shortcut_pressed, KeyType, Shortcut, ShortcutBuilder, ShortcutModifier, Shortcuts,
+    chord_pressed, find_conflicts, format_keys, keypress_to_chord, load_shortcuts, match_sequence,
+    parse_keys, save_shortcuts, shortcut_pressed, shortcuts_from_json, shortcuts_to_json, Chord,
+    KeyType, SequenceMatch, Shortcut, ShortcutBuilder, ShortcutError, ShortcutModifier,
+    ShortcutPresets, Shortcuts,
 };
 
 /// Allows different widgets to interact in their native way
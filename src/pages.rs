@@ -0,0 +1,376 @@
+use rand::Rng;
+use std::time::SystemTime;
+
+use crate::shortcut::chord_to_string;
+use crate::{Bookmark, Favicons, HistoryEntry, LoadError, Message, Shortcuts};
+
+/// Internal pages `IcyBrowser` renders itself instead of passing to the engine
+pub const ABOUT_PAGES: [&str; 5] = [
+    "about:history",
+    "about:bookmarks",
+    "about:settings",
+    "about:downloads",
+    "about:version",
+];
+
+/// What the about pages are generated from
+pub struct AboutInfo<'a> {
+    pub history: &'a [HistoryEntry],
+    pub bookmarks: &'a [Bookmark],
+    pub shortcuts: &'a Shortcuts,
+    pub favicons: &'a Favicons,
+    pub engine: &'a str,
+}
+
+/// Placeholders are replaced with the details of the failed load, already html escaped
+///
+/// `{url}`, `{code}`, `{domain}`, `{description}`
+///
+/// `{nonce}` authenticates the page's actions, sent by logging `icy_browser:{nonce}:<action>`
+pub const DEFAULT_ERROR_PAGE: &str = r#"<!DOCTYPE html>
+<html>
+<head>
+    <meta charset="utf-8">
+    <title>Problem loading page</title>
+    <style>
+        body { font-family: sans-serif; background: #202124; color: #e8eaed; margin: 15% 20%; }
+        h1 { font-size: 1.6em; }
+        code { color: #9aa0a6; }
+        button { margin-top: 2em; padding: 0.5em 1.5em; font-size: 1em; }
+    </style>
+</head>
+<body>
+    <h1>This page couldn't be loaded</h1>
+    <p><code>{url}</code></p>
+    <p>{description}</p>
+    <p><code>{domain} {code}</code></p>
+    <button onclick="console.log('icy_browser:{nonce}:Refresh')">Retry</button>
+</body>
+</html>
+"#;
+
+pub fn error_page(template: &str, error: &LoadError, nonce: &str) -> String {
+    template
+        .replace("{nonce}", nonce)
+        .replace("{url}", &escape_html(&error.url))
+        .replace("{code}", &error.code.to_string())
+        .replace("{domain}", &escape_html(&error.domain))
+        .replace("{description}", &escape_html(&error.description))
+}
+
+/// Generates the html for one of `ABOUT_PAGES`, its actions carrying `nonce`
+pub fn about_page(url: &str, info: &AboutInfo, nonce: &str) -> Option<String> {
+    let (title, body) = match url {
+        "about:history" => ("History", history_body(info.history, info.favicons)),
+        "about:bookmarks" => ("Bookmarks", bookmarks_body(info.bookmarks)),
+        "about:settings" => ("Settings", settings_body(info.shortcuts)),
+        "about:downloads" => (
+            "Downloads",
+            String::from("<p>Downloads are not handled by this browser yet.</p>"),
+        ),
+        "about:version" => (
+            "Version",
+            format!(
+                "<table><tr><td>icy_browser</td><td>{}</td></tr>\
+                <tr><td>Engine</td><td>{}</td></tr></table>",
+                env!("CARGO_PKG_VERSION"),
+                escape_html(info.engine)
+            ),
+        ),
+        _ => return None,
+    };
+    Some(about_layout(title, &body, nonce))
+}
+
+fn history_body(history: &[HistoryEntry], favicons: &Favicons) -> String {
+    if history.is_empty() {
+        return String::from("<p>No pages visited yet.</p>");
+    }
+
+    let rows: String = history
+        .iter()
+        .rev()
+        .map(|entry| {
+            let title = if entry.title.is_empty() {
+                &entry.url
+            } else {
+                &entry.title
+            };
+            // data urls are generated from decoded icons, so they need no escaping
+            let icon = favicons
+                .data_url(&entry.url)
+                .map(|url| format!(r#"<img src="{}" width="16" height="16">"#, url))
+                .unwrap_or_default();
+            format!(
+                "<tr><td>{}</td><td>{}</td><td>{}</td><td><code>{}</code></td></tr>",
+                time_ago(entry.visited),
+                icon,
+                action_link(&format!("GoToUrl:{}", entry.url), title),
+                escape_html(&entry.url)
+            )
+        })
+        .collect();
+    format!(
+        "<p>{}</p><table>{}</table>",
+        action_link("ClearHistory", "Clear history"),
+        rows
+    )
+}
+
+fn bookmarks_body(bookmarks: &[Bookmark]) -> String {
+    if bookmarks.is_empty() {
+        return String::from("<p>No bookmarks.</p>");
+    }
+
+    let rows: String = bookmarks
+        .iter()
+        .map(|bookmark| {
+            format!(
+                "<tr><td>{}</td><td><code>{}</code></td><td>{}</td></tr>",
+                action_link(&format!("GoToUrl:{}", bookmark.url()), bookmark.name()),
+                escape_html(bookmark.url().as_str()),
+                action_link(
+                    &format!("OpenInNewTab:{}", bookmark.url()),
+                    "Open in new tab"
+                )
+            )
+        })
+        .collect();
+    format!("<table>{}</table>", rows)
+}
+
+fn settings_body(shortcuts: &Shortcuts) -> String {
+    let clear_data = format!(
+        "<h2>Browsing data</h2><p>{} | {} | {} | {}</p>\
+        <p>Cookies only sent to servers, data of sites that aren't open and the disk cache \
+        are kept in the profile until it is cleared on the next start.</p><p>{}</p>",
+        action_link("ClearHistory", "Clear history"),
+        action_link(
+            "ClearOpenSiteData",
+            "Clear script readable data of open sites"
+        ),
+        action_link("ClearMemoryCache", "Clear memory cache"),
+        action_link("ClearBrowsingData", "Clear all of these"),
+        action_link(
+            "ClearSiteDataOnNextStart",
+            "Clear all site data and the disk cache on next start"
+        )
+    );
+    if shortcuts.is_empty() {
+        return format!(
+            "{}<h2>Shortcuts</h2><p>No shortcuts configured.</p>",
+            clear_data
+        );
+    }
+
+    let rows: String = shortcuts
+        .iter()
+        .map(|(message, chords)| {
+            let keys = chords
+                .iter()
+                .map(|chord| format!("<kbd>{}</kbd>", escape_html(&chord_to_string(chord))))
+                .collect::<Vec<_>>()
+                .join(" ");
+            format!(
+                "<tr><td>{}</td><td>{}</td></tr>",
+                escape_html(&message.to_string()),
+                keys
+            )
+        })
+        .collect();
+    format!("{}<h2>Shortcuts</h2><table>{}</table>", clear_data, rows)
+}
+
+fn about_layout(title: &str, body: &str, nonce: &str) -> String {
+    let nav: String = ABOUT_PAGES
+        .iter()
+        .map(|page| action_link(&format!("GoToUrl:{}", page), page))
+        .collect::<Vec<_>>()
+        .join(" | ");
+    format!(
+        r#"<!DOCTYPE html>
+<html>
+<head>
+    <meta charset="utf-8">
+    <title>{title}</title>
+    <style>
+        body {{ font-family: sans-serif; background: #202124; color: #e8eaed; margin: 2em 10%; }}
+        a {{ color: #8ab4f8; cursor: pointer; }}
+        td {{ padding: 0.25em 1em 0.25em 0; }}
+        code, kbd {{ color: #9aa0a6; }}
+    </style>
+</head>
+<body>
+    <nav>{nav}</nav>
+    <h1>{title}</h1>
+    {body}
+    <script>
+        document.querySelectorAll('[data-action]').forEach(function(element) {{
+            element.addEventListener('click', function(event) {{
+                event.preventDefault();
+                console.log('icy_browser:{nonce}:' + element.dataset.action);
+            }});
+        }});
+    </script>
+</body>
+</html>
+"#,
+        title = escape_html(title),
+        nav = nav,
+        body = body,
+        nonce = nonce
+    )
+}
+
+/// A link that sends `action` back to `IcyBrowser` when clicked
+fn action_link(action: &str, text: &str) -> String {
+    format!(
+        r#"<a data-action="{}">{}</a>"#,
+        escape_html(action),
+        escape_html(text)
+    )
+}
+
+fn time_ago(time: SystemTime) -> String {
+    let seconds = time.elapsed().map(|elapsed| elapsed.as_secs()).unwrap_or(0);
+    match seconds {
+        0..=59 => String::from("just now"),
+        60..=3599 => format!("{} min ago", seconds / 60),
+        3600..=86399 => format!("{} h ago", seconds / 3600),
+        _ => format!("{} days ago", seconds / 86400),
+    }
+}
+
+/// A random secret for an internal page to prove its actions come from it
+pub fn page_nonce() -> String {
+    format!("{:032x}", rand::thread_rng().gen::<u128>())
+}
+
+/// The actions the internal page shown for `url` offers, error pages only offer a retry
+fn page_actions(url: &str) -> &'static [&'static str] {
+    match url {
+        "about:history" => &["GoToUrl", "ClearHistory"],
+        "about:bookmarks" => &["GoToUrl", "OpenInNewTab"],
+        "about:settings" => &[
+            "GoToUrl",
+            "ClearHistory",
+            "ClearOpenSiteData",
+            "ClearMemoryCache",
+            "ClearBrowsingData",
+            "ClearSiteDataOnNextStart",
+        ],
+        _ if ABOUT_PAGES.contains(&url) => &["GoToUrl"],
+        _ => &["Refresh"],
+    }
+}
+
+/// Converts an action sent by the internal page for `url` into the message it stands for,
+/// ignoring actions the page doesn't offer
+pub fn page_action_message(url: &str, action: &str) -> Option<Message> {
+    let (name, argument) = action.split_once(':').unwrap_or((action, ""));
+    if !page_actions(url).contains(&name) {
+        return None;
+    }
+    match name {
+        "Refresh" => Some(Message::Refresh),
+        "ClearHistory" => Some(Message::ClearHistory),
+        "ClearOpenSiteData" => Some(Message::ClearOpenSiteData),
+        "ClearMemoryCache" => Some(Message::ClearMemoryCache),
+        "ClearBrowsingData" => Some(Message::ClearBrowsingData),
+        "ClearSiteDataOnNextStart" => Some(Message::ClearSiteDataOnNextStart),
+        "GoToUrl" => Some(Message::GoToUrl(argument.to_string())),
+        "OpenInNewTab" => Some(Message::OpenInNewTab(argument.to_string())),
+        _ => None,
+    }
+}
+
+pub fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn load_error(url: &str, description: &str) -> LoadError {
+        LoadError {
+            url: url.to_string(),
+            code: -1003,
+            domain: String::from("NSURLErrorDomain"),
+            description: description.to_string(),
+        }
+    }
+
+    #[test]
+    fn error_page_escapes_load_details() {
+        let error = load_error(
+            "https://example.com/?q=<script>alert('x')</script>&a=\"b\"",
+            "<b>not found</b>",
+        );
+        let html = error_page(DEFAULT_ERROR_PAGE, &error, "0123");
+
+        assert!(!html.contains("<script>"));
+        assert!(!html.contains("<b>"));
+        assert!(html.contains(
+            "https://example.com/?q=&lt;script&gt;alert(&#39;x&#39;)&lt;/script&gt;&amp;a=&quot;b&quot;"
+        ));
+        assert!(html.contains("&lt;b&gt;not found&lt;/b&gt;"));
+        assert!(html.contains("NSURLErrorDomain -1003"));
+    }
+
+    #[test]
+    fn error_page_actions_carry_the_nonce() {
+        let html = error_page(DEFAULT_ERROR_PAGE, &load_error("https://a.b", ""), "0123");
+        assert!(html.contains("console.log('icy_browser:0123:Refresh')"));
+        assert!(!html.contains("{nonce}"));
+    }
+
+    #[test]
+    fn error_page_fills_custom_templates() {
+        let html = error_page(
+            "{url}|{code}|{domain}|{description}",
+            &load_error("https://a.b", "gone & lost"),
+            "",
+        );
+        assert_eq!(html, "https://a.b|-1003|NSURLErrorDomain|gone &amp; lost");
+    }
+
+    #[test]
+    fn pages_only_send_the_actions_they_offer() {
+        assert_eq!(
+            page_action_message("https://a.b", "Refresh"),
+            Some(Message::Refresh)
+        );
+        assert_eq!(
+            page_action_message("https://a.b", "ClearBrowsingData"),
+            None
+        );
+        assert_eq!(
+            page_action_message("https://a.b", "GoToUrl:https://c.d"),
+            None
+        );
+        assert_eq!(
+            page_action_message("about:history", "GoToUrl:https://c.d"),
+            Some(Message::GoToUrl(String::from("https://c.d")))
+        );
+        assert_eq!(
+            page_action_message("about:history", "ClearOpenSiteData"),
+            None
+        );
+        assert_eq!(
+            page_action_message("about:settings", "ClearOpenSiteData"),
+            Some(Message::ClearOpenSiteData)
+        );
+        assert_eq!(page_action_message("about:bookmarks", "CloseTab:1"), None);
+    }
+
+    #[test]
+    fn page_nonces_differ() {
+        assert_ne!(page_nonce(), page_nonce());
+        assert_eq!(page_nonce().len(), 32);
+    }
+}
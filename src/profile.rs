@@ -0,0 +1,106 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tempfile::TempDir;
+
+/// A directory holding everything a browser keeps between runs
+///
+/// Pass the same profile to the engine config and `IcyBrowser::with_profile`
+#[derive(Clone, Debug)]
+pub struct Profile {
+    dir: PathBuf,
+    // deletes the directory once every clone of a temporary profile is dropped
+    temp_dir: Option<Arc<TempDir>>,
+}
+
+impl Profile {
+    /// Uses `dir` as the profile, creating it if needed
+    pub fn new(dir: impl Into<PathBuf>) -> io::Result<Self> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir)?;
+        Ok(Self {
+            dir,
+            temp_dir: None,
+        })
+    }
+
+    /// A profile in a new temporary directory that is deleted when the profile is dropped
+    pub fn temporary() -> io::Result<Self> {
+        let temp_dir = tempfile::Builder::new().prefix("icy_browser").tempdir()?;
+        Ok(Self {
+            dir: temp_dir.path().to_path_buf(),
+            temp_dir: Some(Arc::new(temp_dir)),
+        })
+    }
+
+    pub fn is_temporary(&self) -> bool {
+        self.temp_dir.is_some()
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Where the engine keeps cookies, local storage and its http cache
+    pub fn engine_dir(&self) -> PathBuf {
+        self.dir.join("engine")
+    }
+
+    pub fn favicon_dir(&self) -> PathBuf {
+        self.dir.join("favicons")
+    }
+
+    /// Deletes everything the engine stored, taking effect the next time an engine uses this profile
+    ///
+    /// A running engine can only clear what its open pages reach, see `BrowserEngine::clear_site_data`
+    pub fn clear_engine_data(&self) -> io::Result<()> {
+        match fs::remove_dir_all(self.engine_dir()) {
+            Err(error) if error.kind() != io::ErrorKind::NotFound => Err(error),
+            _ => Ok(()),
+        }
+    }
+
+    /// Has the next engine started with this profile delete everything stored before it loads it
+    ///
+    /// Unlike clearing through a running engine this also removes `HttpOnly` cookies, data of
+    /// sites that aren't open and the disk cache
+    pub fn clear_engine_data_on_start(&self) -> io::Result<()> {
+        fs::write(self.clear_marker(), "")
+    }
+
+    /// Runs a deletion scheduled by `clear_engine_data_on_start`, returning whether there was one
+    ///
+    /// Engines call this before opening the profile
+    pub fn run_scheduled_clear(&self) -> io::Result<bool> {
+        if !self.clear_marker().is_file() {
+            return Ok(false);
+        }
+        self.clear_engine_data()?;
+        fs::remove_file(self.clear_marker())?;
+        Ok(true)
+    }
+
+    fn clear_marker(&self) -> PathBuf {
+        self.dir.join("clear_engine_data")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scheduled_clears_run_once() {
+        let profile = Profile::temporary().unwrap();
+        fs::create_dir_all(profile.engine_dir()).unwrap();
+        fs::write(profile.engine_dir().join("cookies"), "").unwrap();
+        assert!(!profile.run_scheduled_clear().unwrap());
+        assert!(profile.engine_dir().exists());
+
+        profile.clear_engine_data_on_start().unwrap();
+        assert!(profile.run_scheduled_clear().unwrap());
+        assert!(!profile.engine_dir().exists());
+        assert!(!profile.run_scheduled_clear().unwrap());
+    }
+}
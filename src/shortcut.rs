@@ -1,60 +1,77 @@
-use iced::keyboard::{Key, Modifiers};
+use iced::keyboard::{key::Named, Key, Modifiers};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::mem;
+use std::path::Path;
+use std::time::{Duration, Instant};
 
 use super::widgets::Message;
 
-pub struct ShortcutBuilder(Shortcuts);
+/// Collects shortcuts, `build` returns the first invalid one as an error
+pub struct ShortcutBuilder {
+    shortcuts: Shortcuts,
+    error: Option<ShortcutError>,
+}
+
 impl ShortcutBuilder {
     pub fn new() -> Self {
-        ShortcutBuilder(Vec::new())
+        ShortcutBuilder {
+            shortcuts: Vec::new(),
+            error: None,
+        }
     }
 
-    pub fn add_shortcut(mut self, shortcut_action: Message, shortcut_keys: Vec<KeyType>) -> Self {
-        if self.0.iter().filter(|sc| sc.0 == shortcut_action).count() != 0 {
-            panic!("Tried to add a duplicated shortcut");
+    /// Adds a shortcut of one chord
+    ///
+    /// Character keys need a modifier so they don't take over typing, named keys like F5 don't
+    pub fn add_shortcut(self, shortcut_action: Message, shortcut_keys: Vec<KeyType>) -> Self {
+        let needs_modifier = matches!(chord_key(&shortcut_keys), Some(Key::Character(_)));
+        if needs_modifier && chord_modifiers(&shortcut_keys).is_empty() {
+            return self.fail(ShortcutError::MissingModifier(shortcut_action));
         }
+        self.add_sequence(shortcut_action, vec![shortcut_keys])
+    }
 
-        // Must have 1 char key
-        if shortcut_keys
-            .iter()
-            .map(|item| {
-                if let KeyType::Key(_) = item {
-                    return true;
-                } else if let KeyType::Modifier(_) = item {
-                    return false;
-                }
-                unreachable!()
-            })
-            .filter(|item| *item) // if item == true
-            .count()
-            != 1
-        {
-            panic!("Shortcuts MUST have ONLY one Charecter key")
+    /// Adds a shortcut made of chords pressed one after another, e.g. `g g` or `Ctrl+K Ctrl+T`
+    ///
+    /// Unlike `add_shortcut` the chords don't need a modifier
+    pub fn add_sequence(mut self, shortcut_action: Message, chords: Vec<Chord>) -> Self {
+        if self.error.is_some() {
+            return self;
         }
-
-        // Must have at least one modifier key
-        if shortcut_keys
+        if self.shortcuts.iter().any(|sc| sc.0 == shortcut_action) {
+            return self.fail(ShortcutError::Duplicate(shortcut_action));
+        }
+        if chords.is_empty() {
+            return self.fail(ShortcutError::Empty(shortcut_action));
+        }
+        if let Some(error) = chords.iter().find_map(|chord| check_chord(chord)) {
+            return self.fail(error(shortcut_action));
+        }
+        let conflict = self
+            .shortcuts
             .iter()
-            .map(|item| {
-                if let KeyType::Key(_) = item {
-                    return false;
-                } else if let KeyType::Modifier(_) = item {
-                    return true;
-                }
-                unreachable!()
-            })
-            .filter(|item| *item) // if itme == true
-            .count()
-            < 1
-        {
-            panic!("Shortcuts MUST have at least 1 Modifier key")
+            .find(|(_, existing)| sequences_conflict(existing, &chords))
+            .map(|(existing, _)| existing.clone());
+        if let Some(existing) = conflict {
+            return self.fail(ShortcutError::Conflict(shortcut_action, existing));
         }
 
-        self.0.push((shortcut_action, shortcut_keys));
+        self.shortcuts.push((shortcut_action, chords));
         self
     }
 
-    pub fn build(self) -> Shortcuts {
-        self.0
+    pub fn build(self) -> Result<Shortcuts, ShortcutError> {
+        match self.error {
+            Some(error) => Err(error),
+            None => Ok(self.shortcuts),
+        }
+    }
+
+    fn fail(mut self, error: ShortcutError) -> Self {
+        self.error.get_or_insert(error);
+        self
     }
 }
 
@@ -64,11 +81,82 @@ impl Default for ShortcutBuilder {
     }
 }
 
+/// Why a shortcut was rejected, with the action it was for
+#[derive(Debug, Clone, PartialEq)]
+pub enum ShortcutError {
+    /// The action already has a shortcut
+    Duplicate(Message),
+    /// A sequence has no chords
+    Empty(Message),
+    /// A chord has no key or more than one
+    KeyCount(Message),
+    /// The key is `Key::Unidentified` which can't be matched
+    UnidentifiedKey(Message),
+    /// A single character chord without a modifier
+    MissingModifier(Message),
+    /// Both actions have the same keys, or the keys of one start the other's
+    Conflict(Message, Message),
+    /// A key string that can't be parsed
+    InvalidKeys(String),
+    /// A shortcut file names a command that doesn't exist
+    UnknownCommand(String),
+    /// A shortcut file couldn't be read or isn't a json object of strings
+    File(String),
+}
+
+impl fmt::Display for ShortcutError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ShortcutError::Duplicate(action) => write!(f, "{} has more than one shortcut", action),
+            ShortcutError::Empty(action) => write!(f, "The shortcut for {} has no keys", action),
+            ShortcutError::KeyCount(action) => write!(
+                f,
+                "Every chord of the shortcut for {} needs exactly one key",
+                action
+            ),
+            ShortcutError::UnidentifiedKey(action) => {
+                write!(f, "The shortcut for {} uses an unidentified key", action)
+            }
+            ShortcutError::MissingModifier(action) => write!(
+                f,
+                "The shortcut for {} needs a modifier with its character key",
+                action
+            ),
+            ShortcutError::Conflict(action, existing) => write!(
+                f,
+                "The shortcut for {} conflicts with the shortcut for {}",
+                action, existing
+            ),
+            ShortcutError::InvalidKeys(keys) => write!(f, "\"{}\" is not a valid shortcut", keys),
+            ShortcutError::UnknownCommand(name) => write!(f, "There is no command \"{}\"", name),
+            ShortcutError::File(error) => write!(f, "Failed to load shortcuts: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for ShortcutError {}
+
+fn check_chord(chord: &[KeyType]) -> Option<fn(Message) -> ShortcutError> {
+    let keys = chord
+        .iter()
+        .filter(|item| matches!(item, KeyType::Key(_)))
+        .count();
+    if keys != 1 {
+        return Some(ShortcutError::KeyCount);
+    }
+    if chord_key(chord) == Some(&Key::Unidentified) {
+        return Some(ShortcutError::UnidentifiedKey);
+    }
+    None
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub enum ShortcutModifier {
     Shift,
     Ctrl,
     Alt,
+    /// The Windows, Command or Super key
+    Logo,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
@@ -76,33 +164,728 @@ pub enum KeyType {
     Key(iced::keyboard::Key),
     Modifier(ShortcutModifier),
 }
-/// Configures Widget Keyboard Shortcut
-pub type Shortcut = (Message, Vec<KeyType>);
+
+impl KeyType {
+    pub fn character(char: &str) -> Self {
+        KeyType::Key(Key::Character(char.into()))
+    }
+
+    /// Keys without a character, e.g. `Named::F5` or `Named::PageUp`
+    pub fn named(named: Named) -> Self {
+        KeyType::Key(Key::Named(named))
+    }
+}
+
+impl fmt::Display for KeyType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KeyType::Modifier(modifier) => write!(f, "{:?}", modifier),
+            KeyType::Key(Key::Character(char)) => write!(f, "{}", char.to_uppercase()),
+            KeyType::Key(Key::Named(named)) => write!(f, "{:?}", named),
+            KeyType::Key(Key::Unidentified) => write!(f, "?"),
+        }
+    }
+}
+/// Keys that are held down together
+pub type Chord = Vec<KeyType>;
+
+/// Configures Widget Keyboard Shortcut, the chords are pressed one after another
+pub type Shortcut = (Message, Vec<Chord>);
 
 /// Configures Widget Keyboard Shortcuts
 pub type Shortcuts = Vec<Shortcut>;
 
+/// Ready made shortcuts, e.g. `Shortcuts::browser_defaults()`
+pub trait ShortcutPresets {
+    /// The usual browser shortcuts, what `IcyBrowser` starts with
+    fn browser_defaults() -> Self;
+}
+
+impl ShortcutPresets for Shortcuts {
+    fn browser_defaults() -> Self {
+        let bindings = [
+            (Message::CreateTab, "ctrl+t"),
+            (Message::CloseCurrentTab, "ctrl+w"),
+            (Message::ReopenClosedTab, "ctrl+shift+t"),
+            (Message::NextTab, "ctrl+tab"),
+            (Message::PreviousTab, "ctrl+shift+tab"),
+            (Message::SelectTab1, "ctrl+1"),
+            (Message::SelectTab2, "ctrl+2"),
+            (Message::SelectTab3, "ctrl+3"),
+            (Message::SelectTab4, "ctrl+4"),
+            (Message::SelectTab5, "ctrl+5"),
+            (Message::SelectTab6, "ctrl+6"),
+            (Message::SelectTab7, "ctrl+7"),
+            (Message::SelectTab8, "ctrl+8"),
+            (Message::SelectTab9, "ctrl+9"),
+            (Message::FocusAddressBar, "ctrl+l"),
+            (Message::ShowFindBar, "ctrl+f"),
+            (Message::FindNext, "f3"),
+            (Message::FindPrevious, "shift+f3"),
+            (Message::ZoomIn, "ctrl+="),
+            (Message::ZoomOut, "ctrl+-"),
+            (Message::ResetZoom, "ctrl+0"),
+            (Message::GoBackward, "alt+left"),
+            (Message::GoForward, "alt+right"),
+            (Message::Refresh, "ctrl+r"),
+            (Message::ToggleOverlay, "ctrl+e"),
+        ];
+
+        let mut builder = ShortcutBuilder::new();
+        for (action, keys) in bindings {
+            let chords = parse_keys(keys).expect("Invalid default shortcut keys");
+            builder = builder.add_sequence(action, chords);
+        }
+        builder.build().expect("Invalid default shortcuts")
+    }
+}
+
+fn chord_key(chord: &[KeyType]) -> Option<&Key> {
+    chord.iter().find_map(|item| match item {
+        KeyType::Key(key) => Some(key),
+        KeyType::Modifier(_) => None,
+    })
+}
+
+fn chord_modifiers(chord: &[KeyType]) -> Modifiers {
+    chord.iter().fold(Modifiers::empty(), |modifiers, item| {
+        modifiers
+            | match item {
+                KeyType::Modifier(ShortcutModifier::Shift) => Modifiers::SHIFT,
+                KeyType::Modifier(ShortcutModifier::Ctrl) => Modifiers::CTRL,
+                KeyType::Modifier(ShortcutModifier::Alt) => Modifiers::ALT,
+                KeyType::Modifier(ShortcutModifier::Logo) => Modifiers::LOGO,
+                KeyType::Key(_) => Modifiers::empty(),
+            }
+    })
+}
+
+/// Whether `shortcut` is a single chord that was just pressed
 pub fn shortcut_pressed(shortcut: &Shortcut, key: &Key, modifiers: &Modifiers) -> bool {
-    shortcut
-        .1
-        .iter()
-        .map(|s| match s {
-            KeyType::Key(s_key) => {
-                if let iced::keyboard::Key::Character(s_char) = s_key {
-                    if let iced::keyboard::Key::Character(key_char) = key {
-                        key_char == s_char
-                    } else {
-                        false
-                    }
-                } else {
-                    false
+    match shortcut.1.as_slice() {
+        [chord] => chord_pressed(chord, key, modifiers),
+        _ => false,
+    }
+}
+
+/// Matches the key and exactly the held modifiers, so `Ctrl+Shift+T` doesn't press `Ctrl+T`
+pub fn chord_pressed(chord: &[KeyType], key: &Key, modifiers: &Modifiers) -> bool {
+    let keys_match = match (chord_key(chord), key) {
+        // shift changes the case of the pressed character
+        (Some(Key::Character(chord_char)), Key::Character(key_char)) => {
+            chord_char.to_lowercase() == key_char.to_lowercase()
+        }
+        (Some(Key::Named(chord_named)), Key::Named(key_named)) => chord_named == key_named,
+        _ => false,
+    };
+    keys_match && chord_modifiers(chord) == *modifiers
+}
+
+/// How keys pressed so far relate to the configured shortcuts
+#[derive(Debug, Clone, PartialEq)]
+pub enum SequenceMatch {
+    NoMatch,
+    /// The keys start at least one sequence, more are needed
+    Pending,
+    Complete(Message),
+}
+
+/// Matches the keys pressed so far against `shortcuts`
+///
+/// Shortcuts never start another one, see `find_conflicts`, so a complete match is never pending
+pub fn match_sequence(shortcuts: &Shortcuts, pressed: &[(Key, Modifiers)]) -> SequenceMatch {
+    let mut pending = false;
+    for (message, chords) in shortcuts {
+        if chords.len() < pressed.len()
+            || !chords
+                .iter()
+                .zip(pressed)
+                .all(|(chord, (key, modifiers))| chord_pressed(chord, key, modifiers))
+        {
+            continue;
+        }
+        if chords.len() == pressed.len() {
+            return SequenceMatch::Complete(message.clone());
+        }
+        pending = true;
+    }
+    if pending {
+        SequenceMatch::Pending
+    } else {
+        SequenceMatch::NoMatch
+    }
+}
+
+/// Keys of an unfinished sequence, each with what it stands for if the sequence breaks, such as
+/// the event to give back to the page
+pub(crate) struct PendingKeys<T> {
+    keys: Vec<(Key, Modifiers, T)>,
+    since: Instant,
+}
+
+/// What pressing a key did to the pending sequence
+pub(crate) struct Pressed<T> {
+    /// The shortcut the key completed
+    pub run: Option<Message>,
+    /// Keys no shortcut used, oldest first, including the pressed key if it wasn't used
+    pub unused: Vec<T>,
+}
+
+impl<T> PendingKeys<T> {
+    pub fn new() -> Self {
+        Self {
+            keys: Vec::new(),
+            since: Instant::now(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = (&Key, &Modifiers)> {
+        self.keys.iter().map(|(key, modifiers, _)| (key, modifiers))
+    }
+
+    /// Advances the sequence, a key that breaks it gives back the keys held so far
+    pub fn press(
+        &mut self,
+        shortcuts: &Shortcuts,
+        key: Key,
+        modifiers: Modifiers,
+        item: T,
+    ) -> Pressed<T> {
+        self.keys.push((key, modifiers, item));
+        let pressed: Vec<_> = self
+            .keys
+            .iter()
+            .map(|(key, modifiers, _)| (key.clone(), *modifiers))
+            .collect();
+
+        match match_sequence(shortcuts, &pressed) {
+            SequenceMatch::Complete(message) => {
+                self.keys.clear();
+                Pressed {
+                    run: Some(message),
+                    unused: Vec::new(),
                 }
             }
-            KeyType::Modifier(s_mod) => match s_mod {
-                ShortcutModifier::Shift => modifiers.shift(),
-                ShortcutModifier::Ctrl => modifiers.control(),
-                ShortcutModifier::Alt => modifiers.alt(),
-            },
+            SequenceMatch::Pending => {
+                self.since = Instant::now();
+                Pressed {
+                    run: None,
+                    unused: Vec::new(),
+                }
+            }
+            SequenceMatch::NoMatch => {
+                let mut held = mem::take(&mut self.keys);
+                let (key, modifiers, item) = held.pop().expect("the pressed key was just pushed");
+                if held.is_empty() {
+                    return Pressed {
+                        run: None,
+                        unused: vec![item],
+                    };
+                }
+                // the key that broke the sequence may start another one
+                let mut pressed = self.press(shortcuts, key, modifiers, item);
+                let mut unused: Vec<T> = held.into_iter().map(|(_, _, item)| item).collect();
+                unused.append(&mut pressed.unused);
+                pressed.unused = unused;
+                pressed
+            }
+        }
+    }
+
+    /// Ends a sequence nobody finished within `timeout`, giving back its keys
+    pub fn expire(&mut self, timeout: Duration) -> Vec<T> {
+        if self.since.elapsed() <= timeout {
+            return Vec::new();
+        }
+        self.take()
+    }
+
+    /// Ends the sequence, giving back its keys
+    pub fn take(&mut self) -> Vec<T> {
+        mem::take(&mut self.keys)
+            .into_iter()
+            .map(|(_, _, item)| item)
+            .collect()
+    }
+}
+
+/// Describes a chord, e.g. `Ctrl + K`
+pub fn chord_to_string(chord: &[KeyType]) -> String {
+    chord
+        .iter()
+        .map(|key| key.to_string())
+        .collect::<Vec<_>>()
+        .join(" + ")
+}
+
+/// Describes a key press the way shortcuts are displayed
+pub fn keypress_to_string(key: &Key, modifiers: &Modifiers) -> String {
+    chord_to_string(&keypress_to_chord(key, modifiers))
+}
+
+/// The chord a key press would match, used to capture new shortcuts
+pub fn keypress_to_chord(key: &Key, modifiers: &Modifiers) -> Chord {
+    let mut chord = Vec::new();
+    if modifiers.control() {
+        chord.push(KeyType::Modifier(ShortcutModifier::Ctrl));
+    }
+    if modifiers.alt() {
+        chord.push(KeyType::Modifier(ShortcutModifier::Alt));
+    }
+    if modifiers.shift() {
+        chord.push(KeyType::Modifier(ShortcutModifier::Shift));
+    }
+    if modifiers.logo() {
+        chord.push(KeyType::Modifier(ShortcutModifier::Logo));
+    }
+    chord.push(KeyType::Key(key.clone()));
+    chord
+}
+
+/// Whether a key press can be a command even while a text input is focused
+///
+/// Keys held with Ctrl, Alt or Super and function keys aren't text, anything else is typing
+pub(crate) fn is_command_key(key: &Key, modifiers: &Modifiers) -> bool {
+    modifiers.control()
+        || modifiers.alt()
+        || modifiers.logo()
+        || matches!(
+            key,
+            Key::Named(
+                Named::F1
+                    | Named::F2
+                    | Named::F3
+                    | Named::F4
+                    | Named::F5
+                    | Named::F6
+                    | Named::F7
+                    | Named::F8
+                    | Named::F9
+                    | Named::F10
+                    | Named::F11
+                    | Named::F12
+            )
+        )
+}
+
+/// Same key and exactly the same modifiers
+fn chords_equal(a: &[KeyType], b: &[KeyType]) -> bool {
+    let keys_equal = match (chord_key(a), chord_key(b)) {
+        (Some(Key::Character(a)), Some(Key::Character(b))) => a.to_lowercase() == b.to_lowercase(),
+        (a, b) => a == b,
+    };
+    keys_equal && chord_modifiers(a) == chord_modifiers(b)
+}
+
+/// A sequence that starts another can never be pressed without triggering the shorter one
+fn sequences_conflict(a: &[Chord], b: &[Chord]) -> bool {
+    a.iter().zip(b).all(|(a, b)| chords_equal(a, b))
+}
+
+/// Actions other than `action` whose shortcuts conflict with `chords`
+pub fn find_conflicts(shortcuts: &Shortcuts, action: &Message, chords: &[Chord]) -> Vec<Message> {
+    if chords.is_empty() {
+        return Vec::new();
+    }
+    shortcuts
+        .iter()
+        .filter(|(message, existing)| message != action && sequences_conflict(existing, chords))
+        .map(|(message, _)| message.clone())
+        .collect()
+}
+
+/// Names of keys in shortcut strings, the first name of a key is used when formatting
+const NAMED_KEYS: [(&str, Named); 28] = [
+    ("f1", Named::F1),
+    ("f2", Named::F2),
+    ("f3", Named::F3),
+    ("f4", Named::F4),
+    ("f5", Named::F5),
+    ("f6", Named::F6),
+    ("f7", Named::F7),
+    ("f8", Named::F8),
+    ("f9", Named::F9),
+    ("f10", Named::F10),
+    ("f11", Named::F11),
+    ("f12", Named::F12),
+    ("tab", Named::Tab),
+    ("esc", Named::Escape),
+    ("escape", Named::Escape),
+    ("enter", Named::Enter),
+    ("space", Named::Space),
+    ("backspace", Named::Backspace),
+    ("delete", Named::Delete),
+    ("insert", Named::Insert),
+    ("home", Named::Home),
+    ("end", Named::End),
+    ("pageup", Named::PageUp),
+    ("pagedown", Named::PageDown),
+    ("up", Named::ArrowUp),
+    ("down", Named::ArrowDown),
+    ("left", Named::ArrowLeft),
+    ("right", Named::ArrowRight),
+];
+
+/// Parses a key string like `ctrl+shift+t`, or `ctrl+k ctrl+t` for a sequence
+pub fn parse_keys(keys: &str) -> Result<Vec<Chord>, ShortcutError> {
+    let invalid = || ShortcutError::InvalidKeys(keys.to_string());
+
+    let chords: Vec<Chord> = keys
+        .split_whitespace()
+        .map(|chord| {
+            // a trailing `+` is the plus key itself, as in `ctrl++`
+            let (chord, plus) = match chord.strip_suffix("++") {
+                Some(chord) => (chord, true),
+                None if chord == "+" => ("", true),
+                None => (chord, false),
+            };
+            let mut parsed: Chord = chord
+                .split('+')
+                .filter(|part| !part.is_empty())
+                .map(|part| parse_key(part).ok_or_else(invalid))
+                .collect::<Result<_, _>>()?;
+            if plus {
+                parsed.push(KeyType::character("+"));
+            }
+            Ok(parsed)
+        })
+        .collect::<Result<_, _>>()?;
+
+    if chords.is_empty() || chords.iter().any(|chord| check_chord(chord).is_some()) {
+        return Err(invalid());
+    }
+    Ok(chords)
+}
+
+fn parse_key(part: &str) -> Option<KeyType> {
+    let lower = part.to_lowercase();
+    let modifier = match lower.as_str() {
+        "ctrl" | "control" => Some(ShortcutModifier::Ctrl),
+        "shift" => Some(ShortcutModifier::Shift),
+        "alt" | "option" => Some(ShortcutModifier::Alt),
+        "logo" | "super" | "cmd" | "command" | "win" | "meta" => Some(ShortcutModifier::Logo),
+        _ => None,
+    };
+    if let Some(modifier) = modifier {
+        return Some(KeyType::Modifier(modifier));
+    }
+    if let Some((_, named)) = NAMED_KEYS.iter().find(|(name, _)| *name == lower) {
+        return Some(KeyType::named(*named));
+    }
+    (part.chars().count() == 1).then(|| KeyType::character(&lower))
+}
+
+/// Formats chords as a key string that `parse_keys` reads back
+pub fn format_keys(chords: &[Chord]) -> String {
+    chords
+        .iter()
+        .map(|chord| {
+            chord
+                .iter()
+                .map(|key| match key {
+                    KeyType::Modifier(ShortcutModifier::Ctrl) => String::from("ctrl"),
+                    KeyType::Modifier(ShortcutModifier::Shift) => String::from("shift"),
+                    KeyType::Modifier(ShortcutModifier::Alt) => String::from("alt"),
+                    KeyType::Modifier(ShortcutModifier::Logo) => String::from("logo"),
+                    KeyType::Key(Key::Character(char)) => char.to_lowercase(),
+                    KeyType::Key(Key::Named(named)) => NAMED_KEYS
+                        .iter()
+                        .find(|(_, key)| key == named)
+                        .map(|(name, _)| name.to_string())
+                        .unwrap_or_else(|| format!("{:?}", named).to_lowercase()),
+                    KeyType::Key(Key::Unidentified) => String::from("?"),
+                })
+                .collect::<Vec<_>>()
+                .join("+")
         })
-        .all(|s| s) // if s == true
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Reads shortcuts from a json object mapping command names to key strings
+///
+/// `{ "New Tab": "ctrl+t", "Close Tab": "ctrl+k ctrl+w" }`
+pub fn shortcuts_from_json(json: &str) -> Result<Shortcuts, ShortcutError> {
+    let bindings: BTreeMap<String, String> =
+        serde_json::from_str(json).map_err(|error| ShortcutError::File(error.to_string()))?;
+
+    let commands = Message::commands();
+    let mut builder = ShortcutBuilder::new();
+    for (name, keys) in bindings {
+        let command = commands
+            .iter()
+            .find(|command| command.to_string() == name)
+            .ok_or_else(|| ShortcutError::UnknownCommand(name.clone()))?;
+        let mut chords = parse_keys(&keys)?;
+        builder = if chords.len() == 1 {
+            builder.add_shortcut(command.clone(), chords.remove(0))
+        } else {
+            builder.add_sequence(command.clone(), chords)
+        };
+    }
+    builder.build()
+}
+
+pub fn shortcuts_to_json(shortcuts: &Shortcuts) -> String {
+    let bindings: BTreeMap<String, String> = shortcuts
+        .iter()
+        .map(|(message, chords)| (message.to_string(), format_keys(chords)))
+        .collect();
+    serde_json::to_string_pretty(&bindings).expect("Failed to serialize shortcuts")
+}
+
+pub fn load_shortcuts(path: &Path) -> Result<Shortcuts, ShortcutError> {
+    let json = fs::read_to_string(path).map_err(|error| ShortcutError::File(error.to_string()))?;
+    shortcuts_from_json(&json)
+}
+
+pub fn save_shortcuts(path: &Path, shortcuts: &Shortcuts) -> std::io::Result<()> {
+    fs::write(path, shortcuts_to_json(shortcuts))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ctrl(char: &str) -> Chord {
+        vec![
+            KeyType::Modifier(ShortcutModifier::Ctrl),
+            KeyType::character(char),
+        ]
+    }
+
+    fn pressed(chars: &[&str]) -> Vec<(Key, Modifiers)> {
+        chars
+            .iter()
+            .map(|char| (Key::Character((*char).into()), Modifiers::CTRL))
+            .collect()
+    }
+
+    #[test]
+    fn only_modified_and_function_keys_are_commands_while_typing() {
+        assert!(is_command_key(
+            &Key::Character("w".into()),
+            &Modifiers::CTRL
+        ));
+        assert!(is_command_key(&Key::Named(Named::F3), &Modifiers::empty()));
+        assert!(!is_command_key(
+            &Key::Character("w".into()),
+            &Modifiers::empty()
+        ));
+        assert!(!is_command_key(
+            &Key::Character("W".into()),
+            &Modifiers::SHIFT
+        ));
+        assert!(!is_command_key(
+            &Key::Named(Named::Backspace),
+            &Modifiers::empty()
+        ));
+    }
+
+    #[test]
+    fn sequences_wait_for_their_remaining_chords() {
+        let shortcuts = vec![(Message::CloseCurrentTab, vec![ctrl("k"), ctrl("w")])];
+        assert_eq!(
+            match_sequence(&shortcuts, &pressed(&["k"])),
+            SequenceMatch::Pending
+        );
+        assert_eq!(
+            match_sequence(&shortcuts, &pressed(&["k", "w"])),
+            SequenceMatch::Complete(Message::CloseCurrentTab)
+        );
+        assert_eq!(
+            match_sequence(&shortcuts, &pressed(&["k", "t"])),
+            SequenceMatch::NoMatch
+        );
+        assert_eq!(
+            match_sequence(&shortcuts, &pressed(&["w"])),
+            SequenceMatch::NoMatch
+        );
+    }
+
+    #[test]
+    fn modifiers_have_to_match_exactly() {
+        let shortcuts = vec![(Message::CreateTab, vec![ctrl("t")])];
+        let key = Key::Character("T".into());
+        assert_eq!(
+            match_sequence(&shortcuts, &[(key.clone(), Modifiers::CTRL)]),
+            SequenceMatch::Complete(Message::CreateTab)
+        );
+        assert_eq!(
+            match_sequence(&shortcuts, &[(key, Modifiers::CTRL | Modifiers::SHIFT)]),
+            SequenceMatch::NoMatch
+        );
+    }
+
+    fn plain(char: &str) -> Chord {
+        vec![KeyType::character(char)]
+    }
+
+    fn press<'a>(
+        pending: &mut PendingKeys<&'a str>,
+        shortcuts: &Shortcuts,
+        char: &'a str,
+    ) -> (Option<Message>, Vec<&'a str>) {
+        let pressed = pending.press(
+            shortcuts,
+            Key::Character(char.into()),
+            Modifiers::empty(),
+            char,
+        );
+        (pressed.run, pressed.unused)
+    }
+
+    #[test]
+    fn a_broken_sequence_gives_its_keys_back_in_order() {
+        let shortcuts = vec![
+            (Message::GoBackward, vec![plain("g"), plain("g")]),
+            (Message::CreateTab, vec![plain("t")]),
+        ];
+        let mut pending = PendingKeys::new();
+
+        assert_eq!(press(&mut pending, &shortcuts, "g"), (None, vec![]));
+        assert_eq!(press(&mut pending, &shortcuts, "o"), (None, vec!["g", "o"]));
+        assert!(pending.is_empty());
+
+        // the breaking key still runs its own shortcut, after the held keys are given back
+        assert_eq!(press(&mut pending, &shortcuts, "g"), (None, vec![]));
+        assert_eq!(
+            press(&mut pending, &shortcuts, "t"),
+            (Some(Message::CreateTab), vec!["g"])
+        );
+
+        // or starts the sequence again
+        assert_eq!(press(&mut pending, &shortcuts, "g"), (None, vec![]));
+        assert_eq!(press(&mut pending, &shortcuts, "x"), (None, vec!["g", "x"]));
+        assert_eq!(press(&mut pending, &shortcuts, "g"), (None, vec![]));
+        assert_eq!(
+            press(&mut pending, &shortcuts, "g"),
+            (Some(Message::GoBackward), vec![])
+        );
+    }
+
+    #[test]
+    fn expired_sequences_give_their_keys_back() {
+        let shortcuts = vec![(Message::GoBackward, vec![plain("g"), plain("g")])];
+        let mut pending = PendingKeys::new();
+        press(&mut pending, &shortcuts, "g");
+
+        assert!(pending.expire(Duration::from_secs(60)).is_empty());
+        std::thread::sleep(Duration::from_millis(1));
+        assert_eq!(pending.expire(Duration::ZERO), vec!["g"]);
+        assert!(pending.is_empty());
+    }
+
+    #[test]
+    fn the_builder_rejects_prefixes_of_sequences() {
+        let result = ShortcutBuilder::new()
+            .add_shortcut(Message::ToggleOverlay, ctrl("k"))
+            .add_sequence(Message::CloseCurrentTab, vec![ctrl("k"), ctrl("w")])
+            .build();
+        assert_eq!(
+            result,
+            Err(ShortcutError::Conflict(
+                Message::CloseCurrentTab,
+                Message::ToggleOverlay
+            ))
+        );
+    }
+
+    #[test]
+    fn formatted_keys_parse_back() {
+        for keys in [
+            "ctrl+shift+t",
+            "ctrl+k ctrl+w",
+            "g g",
+            "ctrl++",
+            "shift+f3",
+            "alt+left",
+            "logo+esc",
+        ] {
+            assert_eq!(format_keys(&parse_keys(keys).unwrap()), keys);
+        }
+        for (_, chords) in Shortcuts::browser_defaults() {
+            assert_eq!(parse_keys(&format_keys(&chords)), Ok(chords));
+        }
+    }
+
+    #[test]
+    fn key_names_are_case_insensitive_and_have_aliases() {
+        assert_eq!(
+            parse_keys("Control+Shift+T"),
+            Ok(vec![vec![
+                KeyType::Modifier(ShortcutModifier::Ctrl),
+                KeyType::Modifier(ShortcutModifier::Shift),
+                KeyType::character("t"),
+            ]])
+        );
+        assert_eq!(parse_keys("cmd+Escape"), parse_keys("logo+esc"));
+    }
+
+    #[test]
+    fn malformed_keys_are_rejected() {
+        for keys in ["", "ctrl+", "ctrl+a+b", "ctrl+foo", "ctrl+shift"] {
+            assert_eq!(
+                parse_keys(keys),
+                Err(ShortcutError::InvalidKeys(keys.to_string()))
+            );
+        }
+    }
+
+    #[test]
+    fn shortcuts_survive_saving_and_loading() {
+        let defaults = Shortcuts::browser_defaults();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("shortcuts.json");
+        save_shortcuts(&path, &defaults).unwrap();
+
+        let loaded = load_shortcuts(&path).unwrap();
+        assert_eq!(loaded.len(), defaults.len());
+        for shortcut in &defaults {
+            assert!(loaded.contains(shortcut), "{} was lost", shortcut.0);
+        }
+    }
+
+    #[test]
+    fn shortcut_files_name_commands_by_their_display_name() {
+        assert_eq!(
+            shortcuts_from_json(r#"{ "New Tab": "ctrl+t", "Close Tab": "ctrl+k ctrl+w" }"#),
+            Ok(vec![
+                (Message::CloseCurrentTab, vec![ctrl("k"), ctrl("w")]),
+                (Message::CreateTab, vec![ctrl("t")]),
+            ])
+        );
+        assert_eq!(
+            shortcuts_from_json(r#"{ "Update": "ctrl+u" }"#),
+            Err(ShortcutError::UnknownCommand(String::from("Update")))
+        );
+        assert!(matches!(
+            shortcuts_from_json("[]"),
+            Err(ShortcutError::File(_))
+        ));
+    }
+
+    #[test]
+    fn commands_have_unique_names_and_carry_no_data() {
+        let commands = Message::commands();
+        for command in &commands {
+            let name = command.to_string();
+            assert_eq!(
+                commands
+                    .iter()
+                    .filter(|other| other.to_string() == name)
+                    .count(),
+                1,
+                "{} is not unique",
+                name
+            );
+        }
+        assert!(commands.contains(&Message::CreateTab));
+        assert!(!commands.contains(&Message::Update));
+        assert!(!commands.contains(&Message::GoToUrl(String::new())));
+    }
 }
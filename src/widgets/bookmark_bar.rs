@@ -1,14 +1,18 @@
 use iced::{widget::Row, Element};
 
 use super::Message;
-use crate::Bookmark;
+use crate::{Bookmark, Favicons};
 
 /// Creates bookmark bar widget
-pub fn bookmark_bar(bookmarks: &[Bookmark]) -> Element<Message> {
+pub fn bookmark_bar<'a>(bookmarks: &'a [Bookmark], favicons: &Favicons) -> Element<'a, Message> {
     Row::from_vec(
         bookmarks
             .iter()
-            .map(|bookmark| bookmark.as_button().into())
+            .map(|bookmark| {
+                bookmark
+                    .as_button(favicons.get(bookmark.url().as_str()))
+                    .into()
+            })
             .collect(),
     )
     .padding(5)
@@ -1,11 +1,12 @@
-use iced::widget::{center, column, container, mouse_area, opaque, stack};
-use iced::widget::{scrollable, text, Column};
-use iced::{border, Color, Element, Length, Shadow, Theme};
+use iced::widget::{center, column, container, image, mouse_area, opaque, row, stack};
+use iced::widget::{scrollable, text, Column, Space};
+use iced::{border, Alignment, Color, Element, Length, Shadow, Theme};
 use iced_event_wrapper::wrapper;
 use strum_macros::Display;
 
+use super::shortcut_help::shortcut_keys_to_string;
 use crate::engines::DisplayTab;
-use crate::{Bookmark, Message};
+use crate::{Bookmark, Favicons, Message, Shortcuts};
 
 #[derive(Clone, Debug, Display, PartialEq)]
 pub enum ResultType {
@@ -27,6 +28,16 @@ impl ResultType {
             ResultType::Tab(tab) => format!("{} -> {}", tab.title, tab.url),
         }
     }
+
+    /// The page this result leads to, if any
+    pub fn url(&self) -> Option<String> {
+        match self {
+            ResultType::Command(_) => None,
+            ResultType::Bookmark(bookmark) => Some(bookmark.url().to_string()),
+            ResultType::Url(url) => Some(url.to_string()),
+            ResultType::Tab(tab) => Some(tab.url.clone()),
+        }
+    }
 }
 
 pub struct CommandPaletteState {
@@ -40,28 +51,12 @@ pub struct CommandPaletteState {
 impl CommandPaletteState {
     pub fn new(bookmarks: Option<Vec<Bookmark>>) -> Self {
         let mut results: Vec<ResultType> = Vec::new();
-        // This may need to be extended in the future
         results.extend(
-            vec![
-                Message::GoBackward,
-                Message::GoForward,
-                Message::Refresh,
-                Message::GoHome,
-                Message::CloseCurrentTab,
-                Message::CreateTab,
-                Message::HideOverlay,
-                Message::ToggleTabBar,
-                Message::ShowTabBar,
-                Message::HideTabBar,
-                Message::ToggleNavBar,
-                Message::ShowNavBar,
-                Message::HideNavBar,
-                Message::ToggleBookmarkBar,
-                Message::ShowBookmarkBar,
-                Message::HideBookmarkBar,
-            ]
-            .into_iter()
-            .map(ResultType::Command),
+            Message::commands()
+                .into_iter()
+                // the palette is already open
+                .filter(|command| !matches!(command, Message::ShowOverlay | Message::ToggleOverlay))
+                .map(ResultType::Command),
         );
         if let Some(bookmarks) = bookmarks {
             results.extend(bookmarks.into_iter().map(ResultType::Bookmark));
@@ -155,6 +150,8 @@ impl Default for CommandPaletteState {
 pub fn command_palette<'a>(
     base: impl Into<Element<'a, Message>>,
     state: &'a CommandPaletteState,
+    favicons: &Favicons,
+    shortcuts: &Shortcuts,
 ) -> Element<'a, Message> {
     let search = container(
         text(if state.query.is_empty() {
@@ -173,6 +170,8 @@ pub fn command_palette<'a>(
         container(results_list(
             state.filtered_results.as_slice(),
             state.selected_item.clone(),
+            favicons,
+            shortcuts,
         ))
         .width(Length::Fill)
         .height(Length::Fill)
@@ -228,7 +227,12 @@ pub fn command_palette<'a>(
         .into()
 }
 
-fn results_list<'a>(results: &[ResultType], selected_item: Option<String>) -> Element<'a, Message> {
+fn results_list<'a>(
+    results: &[ResultType],
+    selected_item: Option<String>,
+    favicons: &Favicons,
+    shortcuts: &Shortcuts,
+) -> Element<'a, Message> {
     let mut list = Vec::new();
     let mut result_types = Vec::new();
 
@@ -238,7 +242,23 @@ fn results_list<'a>(results: &[ResultType], selected_item: Option<String>) -> El
             list.push(text(result.to_string()).size(20).into())
         }
 
-        let mut text = container(text(format!("   {}", result.inner_name())).size(16));
+        let icon: Element<Message> = match result.url().and_then(|url| favicons.get(&url)) {
+            Some(icon) => image(icon).width(16).height(16).into(),
+            None => Space::with_width(16).into(),
+        };
+        let mut item = row![icon, text(result.inner_name()).size(16)]
+            .spacing(5)
+            .align_y(Alignment::Center);
+        if let ResultType::Command(command) = result {
+            if let Some((_, chords)) = shortcuts.iter().find(|(message, _)| message == command) {
+                item = item.push(Space::with_width(Length::Fill)).push(
+                    text(shortcut_keys_to_string(chords))
+                        .size(14)
+                        .style(text::secondary),
+                );
+            }
+        }
+        let mut text = container(item).padding([0, 10]);
         if let Some(selected_item) = selected_item.as_ref() {
             if result.inner_name() == *selected_item {
                 text = text.style(|theme: &Theme| {
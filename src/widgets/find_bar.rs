@@ -0,0 +1,43 @@
+use iced::widget::{row, text, text_input, Button};
+use iced::{Alignment, Element};
+use iced_aw::core::icons::bootstrap::{icon_to_text, Bootstrap};
+
+use super::Message;
+
+/// Holds the text searched for in the current tab
+#[derive(Debug, Clone, Default)]
+pub struct FindBarState {
+    pub query: String,
+    /// The last search found nothing
+    pub no_matches: bool,
+}
+
+/// Id of the find bar's text input, used to focus it
+pub fn find_input_id() -> text_input::Id {
+    text_input::Id::new("find_bar")
+}
+
+/// Creates a bar to search the current tab, enter finds the next match
+pub fn find_bar(state: &FindBarState) -> Element<Message> {
+    let input = text_input("Find in page", &state.query)
+        .id(find_input_id())
+        .on_input(Message::FindQueryChanged)
+        .on_submit(Message::FindNext);
+
+    let mut row = row![input].spacing(5).padding(5).align_y(Alignment::Center);
+    if state.no_matches {
+        row = row.push(text("No matches").size(14).style(text::danger));
+    }
+    row.extend([
+        Button::new(icon_to_text(Bootstrap::ChevronUp))
+            .on_press(Message::FindPrevious)
+            .into(),
+        Button::new(icon_to_text(Bootstrap::ChevronDown))
+            .on_press(Message::FindNext)
+            .into(),
+        Button::new(icon_to_text(Bootstrap::XLg))
+            .on_press(Message::HideFindBar)
+            .into(),
+    ])
+    .into()
+}
@@ -0,0 +1,40 @@
+use iced::widget::{container, row, stack, text, Row, Space};
+use iced::{border, Element, Length, Theme};
+
+use super::{InputMode, Message};
+
+/// Creates an indicator overlayed on the bottom right of `base` showing the modal input mode and
+/// the keys of a pending sequence
+pub fn key_indicator<'a>(
+    base: impl Into<Element<'a, Message>>,
+    mode: Option<InputMode>,
+    pending: &[String],
+) -> Element<'a, Message> {
+    let mut items: Vec<Element<Message>> = Vec::new();
+    if let Some(mode) = mode {
+        items.push(text(format!("-- {} --", mode)).size(14).into());
+    }
+    if !pending.is_empty() {
+        items.push(text(format!("{} …", pending.join(" "))).size(14).into());
+    }
+
+    if items.is_empty() {
+        return base.into();
+    }
+
+    let indicator = container(Row::from_vec(items).spacing(10))
+        .padding([2, 8])
+        .style(|theme: &Theme| container::Style {
+            background: Some(theme.palette().background.into()),
+            border: border::rounded(4),
+            ..container::Style::default()
+        });
+
+    stack![
+        base.into(),
+        container(row![Space::with_width(Length::Fill), indicator])
+            .align_bottom(Length::Fill)
+            .padding(2)
+    ]
+    .into()
+}
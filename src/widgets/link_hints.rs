@@ -0,0 +1,157 @@
+use iced::widget::{container, text, Stack};
+use iced::{border, Color, Element, Length, Padding};
+
+use super::Message;
+use crate::ClickableElement;
+
+/// Keys used for hint labels, easiest to reach first
+const HINT_KEYS: &str = "asdfghjklqwertyuiop";
+
+pub struct LinkHint {
+    pub label: String,
+    pub element: ClickableElement,
+}
+
+/// Holds the hints shown over the current tab and what has been typed so far
+pub struct LinkHintState {
+    pub hints: Vec<LinkHint>,
+    pub typed: String,
+    /// Open the chosen link in a new tab instead of clicking it
+    pub new_tab: bool,
+}
+
+impl LinkHintState {
+    /// Labels every element, all labels have the same length so none is a prefix of another
+    pub fn new(elements: Vec<ClickableElement>, new_tab: bool) -> Self {
+        let keys: Vec<char> = HINT_KEYS.chars().collect();
+        let mut length = 1;
+        while keys.len().pow(length) < elements.len() {
+            length += 1;
+        }
+
+        let hints = elements
+            .into_iter()
+            .enumerate()
+            .map(|(index, element)| {
+                let mut label = String::new();
+                let mut rest = index;
+                for _ in 0..length {
+                    label.insert(0, keys[rest % keys.len()]);
+                    rest /= keys.len();
+                }
+                LinkHint { label, element }
+            })
+            .collect();
+
+        Self {
+            hints,
+            typed: String::new(),
+            new_tab,
+        }
+    }
+
+    /// Hints whose label starts with what has been typed
+    pub fn visible(&self) -> impl Iterator<Item = &LinkHint> {
+        self.hints
+            .iter()
+            .filter(|hint| hint.label.starts_with(&self.typed))
+    }
+
+    /// The hint whose whole label has been typed
+    pub fn selected(&self) -> Option<&LinkHint> {
+        self.hints.iter().find(|hint| hint.label == self.typed)
+    }
+}
+
+/// Creates hint labels overlayed on `base` at the top left of each element
+pub fn link_hints<'a>(
+    base: impl Into<Element<'a, Message>>,
+    state: &LinkHintState,
+) -> Element<'a, Message> {
+    let mut stack = Stack::new().push(base);
+    for hint in state.visible() {
+        let label = container(text(hint.label.to_uppercase()).size(12).color(Color::BLACK))
+            .padding([0, 3])
+            .style(|_theme| container::Style {
+                background: Some(Color::from_rgb(1.0, 0.85, 0.3).into()),
+                border: border::rounded(2),
+                ..container::Style::default()
+            });
+        stack = stack.push(
+            container(label)
+                .padding(Padding {
+                    top: hint.element.bounds.y.max(0.0),
+                    left: hint.element.bounds.x.max(0.0),
+                    ..Padding::ZERO
+                })
+                .width(Length::Fill)
+                .height(Length::Fill),
+        );
+    }
+    stack.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use iced::Rectangle;
+
+    fn elements(count: usize) -> Vec<ClickableElement> {
+        (0..count)
+            .map(|index| ClickableElement {
+                bounds: Rectangle::new(iced::Point::new(0.0, index as f32), iced::Size::ZERO),
+                url: Some(format!("https://example.com/{}", index)),
+            })
+            .collect()
+    }
+
+    fn labels(state: &LinkHintState) -> Vec<&str> {
+        state.hints.iter().map(|hint| hint.label.as_str()).collect()
+    }
+
+    #[test]
+    fn labels_use_the_easiest_keys_first() {
+        let state = LinkHintState::new(elements(3), false);
+        assert_eq!(labels(&state), vec!["a", "s", "d"]);
+    }
+
+    #[test]
+    fn labels_grow_once_single_keys_run_out() {
+        let count = HINT_KEYS.len();
+        let state = LinkHintState::new(elements(count), false);
+        assert!(state.hints.iter().all(|hint| hint.label.len() == 1));
+
+        let state = LinkHintState::new(elements(count + 1), false);
+        assert!(state.hints.iter().all(|hint| hint.label.len() == 2));
+        assert_eq!(labels(&state)[..3], ["aa", "as", "ad"]);
+    }
+
+    #[test]
+    fn labels_are_unique_and_never_prefix_another() {
+        let state = LinkHintState::new(elements(500), false);
+        let labels = labels(&state);
+        for (index, label) in labels.iter().enumerate() {
+            assert!(labels
+                .iter()
+                .enumerate()
+                .all(|(other, other_label)| other == index || !other_label.starts_with(label)));
+        }
+    }
+
+    #[test]
+    fn typing_narrows_the_hints_down_to_one() {
+        let mut state = LinkHintState::new(elements(40), true);
+        state.typed = String::from("s");
+        assert!(state.visible().all(|hint| hint.label.starts_with('s')));
+        assert!(state.selected().is_none());
+
+        state.typed = String::from("sd");
+        assert_eq!(state.visible().count(), 1);
+        assert_eq!(
+            state
+                .selected()
+                .and_then(|hint| hint.element.url.as_deref()),
+            Some("https://example.com/21")
+        );
+    }
+}
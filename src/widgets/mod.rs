@@ -1,11 +1,15 @@
 use clipboard_rs::Clipboard;
 use iced::keyboard::{self, key};
-use iced::widget::{self, column};
+use iced::widget::{self, column, text_input};
 use iced::{mouse, Element, Event, Point, Size, Subscription, Task};
 use iced_on_focus_widget::hoverable;
 use nav_bar::NavBarState;
+use std::collections::HashMap;
+use std::path::PathBuf;
 use std::string::ToString;
-use std::time::Duration;
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
+use strum::IntoEnumIterator;
 use strum_macros::{Display, EnumIter};
 use url::Url;
 
@@ -15,6 +19,9 @@ pub use browser_view::browser_view;
 pub mod nav_bar;
 pub use nav_bar::nav_bar;
 
+pub mod find_bar;
+pub use find_bar::{find_bar, FindBarState};
+
 pub mod tab_bar;
 pub use tab_bar::tab_bar;
 
@@ -24,9 +31,34 @@ pub use bookmark_bar::bookmark_bar;
 pub mod command_palette;
 pub use command_palette::{command_palette, CommandPaletteState, ResultType};
 
+pub mod status_bar;
+pub use status_bar::{status_bar, StatusBarState};
+
+pub mod key_indicator;
+pub use key_indicator::key_indicator;
+
+pub mod link_hints;
+pub use link_hints::{link_hints, LinkHintState};
+
+pub mod shortcut_help;
+pub use shortcut_help::shortcut_help;
+
+pub mod shortcut_editor;
+pub use shortcut_editor::{shortcut_editor, ShortcutEditorState};
+
 use crate::{
-    engines::BrowserEngine, shortcut_pressed, to_url, Bookmark, Bookmarks, ImageInfo, Shortcuts,
-    TabInfo, TabSelectionType,
+    engines::BrowserEngine,
+    favicon::{favicon_url, fetch_favicon},
+    keypress_to_chord, load_shortcuts, match_sequence,
+    pages::{
+        about_page, error_page, page_action_message, page_nonce, AboutInfo, ABOUT_PAGES,
+        DEFAULT_ERROR_PAGE,
+    },
+    save_shortcuts,
+    shortcut::{is_command_key, keypress_to_string, PendingKeys},
+    shortcut_pressed, to_url, Bookmark, Bookmarks, Chord, Favicons, HistoryEntry, ImageInfo,
+    LoadError, LoadState, PopupRequest, Profile, ShortcutBuilder, ShortcutError, ShortcutPresets,
+    Shortcuts, TabInfo, TabSelectionType, DESKTOP_USER_AGENT, MOBILE_USER_AGENT,
 };
 
 /// Allows users to implement their own custom view view with custom widgets and configurations
@@ -43,7 +75,7 @@ pub trait CustomWidget<Message, Info: TabInfo + Clone> {
 }
 
 // Options exist only to have defaults for EnumIter
-#[derive(Debug, Clone, PartialEq, Display, EnumIter)]
+#[derive(Debug, Clone, PartialEq, Default, Display, EnumIter)]
 pub enum Message {
     // Commands visible to user with shortcuts and command palette
     #[strum(to_string = "Go Backward (Back)")]
@@ -51,6 +83,7 @@ pub enum Message {
     #[strum(to_string = "Go Forward (Forward)")]
     GoForward,
     Refresh,
+    Stop,
     #[strum(to_string = "Go Home (Home)")]
     GoHome,
     #[strum(to_string = "Go To Url")]
@@ -63,6 +96,34 @@ pub enum Message {
     CloseCurrentTab,
     #[strum(to_string = "New Tab")]
     CreateTab,
+    #[strum(to_string = "Reopen Closed Tab")]
+    ReopenClosedTab,
+    #[strum(to_string = "Next Tab")]
+    NextTab,
+    #[strum(to_string = "Previous Tab")]
+    PreviousTab,
+    #[strum(to_string = "Select Tab 1")]
+    SelectTab1,
+    #[strum(to_string = "Select Tab 2")]
+    SelectTab2,
+    #[strum(to_string = "Select Tab 3")]
+    SelectTab3,
+    #[strum(to_string = "Select Tab 4")]
+    SelectTab4,
+    #[strum(to_string = "Select Tab 5")]
+    SelectTab5,
+    #[strum(to_string = "Select Tab 6")]
+    SelectTab6,
+    #[strum(to_string = "Select Tab 7")]
+    SelectTab7,
+    #[strum(to_string = "Select Tab 8")]
+    SelectTab8,
+    #[strum(to_string = "Select Tab 9")]
+    SelectTab9,
+    #[strum(to_string = "Open In New Tab")]
+    OpenInNewTab(String),
+    #[strum(to_string = "Open In Background Tab")]
+    OpenInBackgroundTab(String),
     #[strum(to_string = "Toggle Command Palette")]
     ToggleOverlay,
     #[strum(to_string = "Show Command Palette")]
@@ -87,17 +148,205 @@ pub enum Message {
     ShowBookmarkBar,
     #[strum(to_string = "Hide Bookmark Bar")]
     HideBookmarkBar,
+    #[strum(to_string = "Clear History")]
+    ClearHistory,
+    #[strum(to_string = "Clear Script Readable Data Of Open Sites")]
+    ClearOpenSiteData,
+    #[strum(to_string = "Clear Memory Cache")]
+    ClearMemoryCache,
+    #[strum(to_string = "Clear History, Memory Cache And Script Readable Data Of Open Sites")]
+    ClearBrowsingData,
+    /// Removes every cookie, all site data and the disk cache the next time the browser starts,
+    /// needs `IcyBrowser::with_profile`
+    #[strum(to_string = "Clear All Site Data And Disk Cache On Next Start")]
+    ClearSiteDataOnNextStart,
+    #[strum(to_string = "Toggle Status Bar")]
+    ToggleStatusBar,
+    #[strum(to_string = "Zoom In")]
+    ZoomIn,
+    #[strum(to_string = "Zoom Out")]
+    ZoomOut,
+    #[strum(to_string = "Reset Zoom")]
+    ResetZoom,
+    #[strum(to_string = "Scroll Down")]
+    ScrollDown,
+    #[strum(to_string = "Scroll Up")]
+    ScrollUp,
+    #[strum(to_string = "Scroll Half Page Down")]
+    ScrollHalfPageDown,
+    #[strum(to_string = "Scroll Half Page Up")]
+    ScrollHalfPageUp,
+    #[strum(to_string = "Scroll To Top")]
+    ScrollToTop,
+    #[strum(to_string = "Scroll To Bottom")]
+    ScrollToBottom,
+    #[strum(to_string = "Show Keyboard Shortcuts")]
+    ShowShortcutHelp,
+    #[strum(to_string = "Hide Keyboard Shortcuts")]
+    HideShortcutHelp,
+    #[strum(to_string = "Edit Keyboard Shortcuts")]
+    ShowShortcutEditor,
+    #[strum(to_string = "Close Shortcut Editor")]
+    HideShortcutEditor,
+    #[strum(to_string = "Focus Address Bar")]
+    FocusAddressBar,
+    #[strum(to_string = "Focus Page")]
+    FocusPage,
+    #[strum(to_string = "Find In Page")]
+    ShowFindBar,
+    #[strum(to_string = "Close Find Bar")]
+    HideFindBar,
+    #[strum(to_string = "Find Next")]
+    FindNext,
+    #[strum(to_string = "Find Previous")]
+    FindPrevious,
+    #[strum(to_string = "Show Link Hints")]
+    ShowLinkHints,
+    #[strum(to_string = "Show Link Hints For New Tab")]
+    ShowLinkHintsInNewTab,
+    #[strum(to_string = "Hide Link Hints")]
+    HideLinkHints,
+    #[strum(to_string = "Normal Mode")]
+    EnterNormalMode,
+    #[strum(to_string = "Insert Mode")]
+    EnterInsertMode,
+    #[strum(to_string = "Request Desktop Site")]
+    RequestDesktopSite,
+    #[strum(to_string = "Use Mobile User Agent")]
+    UseMobileUserAgent,
+    #[strum(to_string = "Use Default User Agent")]
+    UseDefaultUserAgent,
 
     // Internal only - for widgets
+    #[default]
     Update,
     UrlChanged(String),
     UpdateUrl,
     CommandPaletteQueryChanged,
     CommandPaletteKeyboardEvent(Option<keyboard::Event>),
+    FindQueryChanged(String),
     SendKeyboardEvent(Option<keyboard::Event>),
+    /// A key the page view saw, only sent on when `IcyBrowser::subscription` isn't running
+    PageKeyboardEvent(Option<keyboard::Event>),
     SendMouseEvent(Point, Option<mouse::Event>),
     UpdateViewSize(Size<u32>),
-    IcedEvent(Option<iced::Event>),
+    /// A window event and whether a widget, like a focused text input, captured it
+    IcedEvent(Option<iced::Event>, bool),
+    AllowBlockedPopups,
+    DismissBlockedPopups,
+    FaviconFetched(String, Option<Vec<u8>>),
+    /// Starts capturing new keys for this command
+    ShortcutEditorSelect(Box<Message>),
+    ShortcutEditorApply,
+    ShortcutEditorCancel,
+    ShortcutEditorRemove(Box<Message>),
+    ShortcutEditorReset,
+    ShortcutEditorSave,
+}
+
+impl Message {
+    /// Commands that can be bound to shortcuts and run from the command palette
+    pub fn commands() -> Vec<Message> {
+        Message::iter()
+            .filter(|message| message.category().is_some())
+            .collect()
+    }
+
+    /// The group a command is listed under in the shortcut help, `None` for messages that
+    /// aren't commands because they carry data or are only sent by widgets
+    pub fn category(&self) -> Option<&'static str> {
+        match self {
+            Message::GoBackward
+            | Message::GoForward
+            | Message::Refresh
+            | Message::Stop
+            | Message::GoHome => Some("Navigation"),
+            Message::CloseCurrentTab
+            | Message::CreateTab
+            | Message::ReopenClosedTab
+            | Message::NextTab
+            | Message::PreviousTab
+            | Message::SelectTab1
+            | Message::SelectTab2
+            | Message::SelectTab3
+            | Message::SelectTab4
+            | Message::SelectTab5
+            | Message::SelectTab6
+            | Message::SelectTab7
+            | Message::SelectTab8
+            | Message::SelectTab9 => Some("Tabs"),
+            Message::ScrollDown
+            | Message::ScrollUp
+            | Message::ScrollHalfPageDown
+            | Message::ScrollHalfPageUp
+            | Message::ScrollToTop
+            | Message::ScrollToBottom
+            | Message::ZoomIn
+            | Message::ZoomOut
+            | Message::ResetZoom
+            | Message::ShowLinkHints
+            | Message::ShowLinkHintsInNewTab
+            | Message::HideLinkHints
+            | Message::ShowFindBar
+            | Message::HideFindBar
+            | Message::FindNext
+            | Message::FindPrevious => Some("Page"),
+            Message::EnterNormalMode | Message::EnterInsertMode => Some("Modes"),
+            Message::ClearHistory
+            | Message::ClearOpenSiteData
+            | Message::ClearMemoryCache
+            | Message::ClearBrowsingData
+            | Message::ClearSiteDataOnNextStart
+            | Message::RequestDesktopSite
+            | Message::UseMobileUserAgent
+            | Message::UseDefaultUserAgent => Some("Site Data"),
+            Message::ToggleOverlay
+            | Message::ShowOverlay
+            | Message::HideOverlay
+            | Message::ToggleTabBar
+            | Message::ShowTabBar
+            | Message::HideTabBar
+            | Message::ToggleNavBar
+            | Message::ShowNavBar
+            | Message::HideNavBar
+            | Message::ToggleBookmarkBar
+            | Message::ShowBookmarkBar
+            | Message::HideBookmarkBar
+            | Message::ToggleStatusBar
+            | Message::ShowShortcutHelp
+            | Message::HideShortcutHelp
+            | Message::ShowShortcutEditor
+            | Message::HideShortcutEditor
+            | Message::FocusAddressBar
+            | Message::FocusPage => Some("Interface"),
+            // listed without a wildcard so every new message has to be sorted in
+            Message::GoToUrl(_)
+            | Message::ChangeTab(_)
+            | Message::CloseTab(_)
+            | Message::OpenInNewTab(_)
+            | Message::OpenInBackgroundTab(_)
+            | Message::Update
+            | Message::UrlChanged(_)
+            | Message::UpdateUrl
+            | Message::CommandPaletteQueryChanged
+            | Message::CommandPaletteKeyboardEvent(_)
+            | Message::FindQueryChanged(_)
+            | Message::SendKeyboardEvent(_)
+            | Message::PageKeyboardEvent(_)
+            | Message::SendMouseEvent(..)
+            | Message::UpdateViewSize(_)
+            | Message::IcedEvent(..)
+            | Message::AllowBlockedPopups
+            | Message::DismissBlockedPopups
+            | Message::FaviconFetched(..)
+            | Message::ShortcutEditorSelect(_)
+            | Message::ShortcutEditorApply
+            | Message::ShortcutEditorCancel
+            | Message::ShortcutEditorRemove(_)
+            | Message::ShortcutEditorReset
+            | Message::ShortcutEditorSave => None,
+        }
+    }
 }
 
 /// Allows the user to write a custom homepage
@@ -107,6 +356,81 @@ pub enum HomepageType<'a> {
     Custom(&'a str),
 }
 
+/// What keyboard input is delivered to
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FocusTarget {
+    /// The current tab's page, keys no shortcut uses are sent to the engine
+    #[default]
+    Page,
+    NavBar,
+    CommandPalette,
+    FindBar,
+    /// The shortcut help or shortcut editor
+    Panel,
+}
+
+/// Decides what happens when a page tries to open a new window
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PopupPolicy {
+    /// Open every request as a new tab
+    #[default]
+    Allow,
+    /// Open no requests, only showing a count in the nav bar
+    ///
+    /// The requests are kept until the tab closes or `DismissBlockedPopups`, so
+    /// `AllowBlockedPopups` can still open them
+    Block,
+    /// Hold requests until the user allows them from the nav bar
+    Ask,
+}
+
+/// Selects what `IcyBrowser::clear_browsing_data` removes
+///
+/// `HttpOnly` cookies, data of sites that aren't open and the disk cache are only removed
+/// before the engine starts, see `Message::ClearSiteDataOnNextStart`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BrowsingData {
+    /// Visited pages and the closed tabs that can be reopened
+    pub history: bool,
+    /// Data pages of the sites open in a tab can reach, see `BrowserEngine::clear_site_data`
+    pub open_site_data: bool,
+    /// The engine's memory cache and site icons
+    pub memory_cache: bool,
+}
+
+impl BrowsingData {
+    pub const ALL: Self = Self {
+        history: true,
+        open_site_data: true,
+        memory_cache: true,
+    };
+}
+
+/// Modes of the optional modal input layer
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Display)]
+pub enum InputMode {
+    /// Keys navigate the page instead of being sent to it
+    #[default]
+    #[strum(to_string = "NORMAL")]
+    Normal,
+    /// Keys are sent to the page
+    #[strum(to_string = "INSERT")]
+    Insert,
+    /// The command palette is open
+    #[strum(to_string = "COMMAND")]
+    Command,
+}
+
+/// Generated html shown in a tab, trusted to send page actions until the tab navigates away
+struct InternalPage {
+    /// The url the page stands in for
+    url: String,
+    /// Secret the page's actions have to carry, so other pages can't forge them
+    nonce: String,
+    /// The tab's `load_count` before the page was shown
+    load_count: u32,
+}
+
 pub struct IcyBrowser<Engine: BrowserEngine> {
     engine: Engine,
     home: Url,
@@ -115,37 +439,126 @@ pub struct IcyBrowser<Engine: BrowserEngine> {
     with_tab_bar: bool,
     with_nav_bar: bool,
     with_bookmark_bar: bool,
+    with_status_bar: bool,
     bookmarks: Option<Bookmarks>,
     show_overlay: bool,
     shortcuts: Shortcuts,
+    /// Shortcuts restored by `reset_to_defaults`
+    default_shortcuts: Shortcuts,
     view_size: Size<u32>,
+    popup_policy: PopupPolicy,
+    blocked_popups: HashMap<u32, Vec<PopupRequest>>,
+    modifiers: keyboard::Modifiers,
+    // a press that opened a link instead of reaching the page, so its release doesn't either
+    swallowed_button: Option<mouse::Button>,
+    favicons: Favicons,
+    error_page: String,
+    /// Tabs showing generated html
+    internal_pages: HashMap<u32, InternalPage>,
+    /// Failed loads by tab with the tab's load count, shown once their tab is current
+    load_errors: HashMap<u32, (u32, LoadError)>,
+    history: Vec<HistoryEntry>,
+    /// Urls of closed tabs, most recently closed last
+    closed_tabs: Vec<String>,
+    // last url recorded in history for each tab
+    recorded_urls: HashMap<u32, String>,
+    /// User agents to use instead of the engine's default, by host
+    user_agents: HashMap<String, String>,
+    profile: Option<Profile>,
+    private: bool,
+    /// Keys pressed so far of a shortcut sequence, with the events the page didn't get yet
+    pending_keys: PendingKeys<Option<keyboard::Event>>,
+    chord_timeout: Duration,
+    /// `None` unless modal input is enabled
+    input_mode: Option<InputMode>,
+    // normal mode keys of an unfinished command, e.g. the first `g` of `gg`
+    normal_keys: String,
+    // last reported input focus, modes only switch when it changes
+    input_focused: bool,
+    // set by the first event of `subscription`, which then delivers keys instead of the page view
+    events_subscribed: bool,
+    link_hints: Option<LinkHintState>,
+    /// `None` unless the find bar is open
+    find_bar: Option<FindBarState>,
+    focus: FocusTarget,
+    show_shortcut_help: bool,
+    /// `None` unless the shortcut editor is open
+    shortcut_editor: Option<ShortcutEditorState>,
+    /// Shortcuts are reloaded from here when it changes
+    shortcuts_file: Option<PathBuf>,
+    shortcuts_modified: Option<SystemTime>,
+    shortcuts_checked: Instant,
+    shortcuts_error: Option<ShortcutError>,
 }
 
 impl<Engine: BrowserEngine> Default for IcyBrowser<Engine> {
     fn default() -> Self {
+        Self::from_engine(Engine::new())
+    }
+}
+
+impl<Engine: BrowserEngine> IcyBrowser<Engine> {
+    const HOME: &'static str = "https://google.com";
+    const ZOOM_STEP: f32 = 0.1;
+    const ZOOM_MIN: f32 = 0.3;
+    const ZOOM_MAX: f32 = 5.0;
+    const CHORD_TIMEOUT: Duration = Duration::from_millis(1000);
+    const SCROLL_STEP: f32 = 60.0;
+    const SHORTCUTS_FILE_POLL: Duration = Duration::from_millis(1000);
+
+    /// Panics if the engine can't be created, use `from_engine` to handle that instead
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Uses an engine that was configured by the caller, e.g. `Ultralight::try_with_config`
+    pub fn from_engine(engine: Engine) -> Self {
         let home = Url::parse(Self::HOME).unwrap();
         Self {
-            engine: Engine::new(),
+            engine,
             home,
             nav_bar_state: NavBarState::new(),
             command_palette_state: CommandPaletteState::new(None),
             with_tab_bar: false,
             with_nav_bar: false,
             with_bookmark_bar: false,
+            with_status_bar: false,
             bookmarks: None,
             show_overlay: false,
-            shortcuts: Shortcuts::default(),
+            shortcuts: Shortcuts::browser_defaults(),
+            default_shortcuts: Shortcuts::browser_defaults(),
             view_size: Size::new(800, 800),
+            popup_policy: PopupPolicy::default(),
+            blocked_popups: HashMap::new(),
+            modifiers: keyboard::Modifiers::default(),
+            swallowed_button: None,
+            favicons: Favicons::default(),
+            error_page: DEFAULT_ERROR_PAGE.to_string(),
+            internal_pages: HashMap::new(),
+            load_errors: HashMap::new(),
+            history: Vec::new(),
+            closed_tabs: Vec::new(),
+            recorded_urls: HashMap::new(),
+            user_agents: HashMap::new(),
+            profile: None,
+            private: false,
+            pending_keys: PendingKeys::new(),
+            chord_timeout: Self::CHORD_TIMEOUT,
+            input_mode: None,
+            normal_keys: String::new(),
+            input_focused: false,
+            events_subscribed: false,
+            link_hints: None,
+            find_bar: None,
+            focus: FocusTarget::default(),
+            show_shortcut_help: false,
+            shortcut_editor: None,
+            shortcuts_file: None,
+            shortcuts_modified: None,
+            shortcuts_checked: Instant::now(),
+            shortcuts_error: None,
         }
     }
-}
-
-impl<Engine: BrowserEngine> IcyBrowser<Engine> {
-    const HOME: &'static str = "https://google.com";
-
-    pub fn new() -> Self {
-        Self::default()
-    }
 
     pub fn with_homepage(mut self, homepage: HomepageType) -> Self {
         match homepage {
@@ -180,17 +593,253 @@ impl<Engine: BrowserEngine> IcyBrowser<Engine> {
         self
     }
 
+    pub fn with_status_bar(mut self) -> Self {
+        self.with_status_bar = true;
+        self
+    }
+
+    /// Replaces `Shortcuts::browser_defaults`, and the shortcuts restored by `reset_to_defaults`
     pub fn with_custom_shortcuts(mut self, shortcuts: Shortcuts) -> Self {
+        self.default_shortcuts = shortcuts.clone();
         self.shortcuts = shortcuts;
         self
     }
 
+    /// Loads shortcuts from a json file, reloading them whenever it changes
+    ///
+    /// A missing file is created with the current shortcuts. Invalid files keep the previous
+    /// shortcuts and are reported by `shortcuts_error`, see `shortcuts_from_json` for the format
+    pub fn with_shortcuts_file(mut self, path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        if !path.exists() {
+            if let Err(error) = save_shortcuts(&path, &self.shortcuts) {
+                self.shortcuts_error = Some(ShortcutError::File(error.to_string()));
+            }
+        }
+        self.shortcuts_file = Some(path);
+        self.reload_shortcuts();
+        self
+    }
+
+    /// Sets where site icons are cached on disk, otherwise they're only kept in memory
+    ///
+    /// `with_profile` already caches them in the profile
+    pub fn with_favicon_cache(mut self, path: PathBuf) -> Self {
+        self.favicons = Favicons::new(Some(path));
+        self
+    }
+
+    /// Serves `scheme` urls, such as `app://dashboard`, from `handler` instead of the network
+    ///
+    /// The handler returns the response body and its mime type, or `None` when the url is unknown
+    pub fn with_scheme_handler(
+        mut self,
+        scheme: &str,
+        handler: impl Fn(&Url) -> Option<(Vec<u8>, String)> + Send + Sync + 'static,
+    ) -> Self {
+        self.engine
+            .register_scheme_handler(scheme, Arc::new(handler));
+        self
+    }
+
+    /// Replaces the page shown when a load fails, see `DEFAULT_ERROR_PAGE` for its placeholders
+    pub fn with_error_page(mut self, template: &str) -> Self {
+        self.error_page = template.to_string();
+        self
+    }
+
+    pub fn with_popup_policy(mut self, policy: PopupPolicy) -> Self {
+        self.popup_policy = policy;
+        self
+    }
+
+    /// Keeps site icons in `profile`, the engine has to be configured with the same profile
+    pub fn with_profile(mut self, profile: Profile) -> Self {
+        if !self.private {
+            self.favicons = Favicons::new(Some(profile.favicon_dir()));
+        }
+        self.profile = Some(profile);
+        self
+    }
+
+    /// Browses without recording history or caching site icons on disk
+    ///
+    /// Closing the last tab clears what its page can reach, such as script readable cookies and
+    /// local storage. Everything else the engine holds, including `HttpOnly` cookies and its
+    /// temporary profile, is only discarded when the engine is dropped. Panics unless the engine
+    /// keeps its data out of persistent storage, e.g. when created with `UltralightConfig::private`
+    pub fn with_private_mode(mut self) -> Self {
+        assert!(
+            self.engine.is_private(),
+            "private mode needs an engine that doesn't store site data on disk"
+        );
+        self.private = true;
+        self.favicons = Favicons::default();
+        self
+    }
+
+    pub fn is_private(&self) -> bool {
+        self.private
+    }
+
+    /// Sends `user_agent` instead of the engine's default when browsing `host`
+    ///
+    /// Applied to loads the browser starts, such as new tabs and the address bar, while links
+    /// followed inside a page keep the tab's user agent
+    pub fn with_user_agent_override(mut self, host: &str, user_agent: &str) -> Self {
+        self.user_agents
+            .insert(host.to_string(), user_agent.to_string());
+        self
+    }
+
+    /// Adds vim style normal, insert and command modes
+    ///
+    /// In normal mode `j`/`k` scroll, `d`/`u` scroll half a page, `gg`/`G` go to the top or
+    /// bottom, `H`/`L` go back or forward, `f`/`F` show link hints, `:` opens the command palette
+    /// and `i` enters insert mode. Insert mode is entered when a text field is focused and left
+    /// with Escape.
+    pub fn with_modal_input(mut self) -> Self {
+        self.input_mode = Some(InputMode::Normal);
+        self
+    }
+
+    /// Where keyboard input currently goes
+    pub fn focus(&self) -> FocusTarget {
+        self.focus
+    }
+
+    /// The current mode, `None` when modal input isn't enabled
+    pub fn input_mode(&self) -> Option<InputMode> {
+        self.input_mode
+    }
+
+    /// How long a shortcut sequence waits for its next key before its keys go to the page
+    pub fn with_chord_timeout(mut self, timeout: Duration) -> Self {
+        self.chord_timeout = timeout;
+        self
+    }
+
     pub fn build(self) -> Self {
         let mut build = Self { ..self };
         let _ = build.update(Message::CreateTab); // disregaurd task::none() for update
         build
     }
 
+    /// Why the shortcuts file was last rejected, cleared once it loads
+    pub fn shortcuts_error(&self) -> Option<&ShortcutError> {
+        self.shortcuts_error.as_ref()
+    }
+
+    pub fn shortcuts(&self) -> &Shortcuts {
+        &self.shortcuts
+    }
+
+    /// Binds `action` to `chords`, replacing its current shortcut
+    ///
+    /// The keys are checked like `ShortcutBuilder` does, an error leaves the shortcuts unchanged
+    pub fn set_shortcut(
+        &mut self,
+        action: Message,
+        chords: Vec<Chord>,
+    ) -> Result<(), ShortcutError> {
+        let mut builder = ShortcutBuilder::new();
+        for (message, existing) in self
+            .shortcuts
+            .iter()
+            .filter(|(message, _)| *message != action)
+        {
+            builder = builder.add_sequence(message.clone(), existing.clone());
+        }
+        builder = match chords.as_slice() {
+            [chord] => builder.add_shortcut(action.clone(), chord.clone()),
+            _ => builder.add_sequence(action.clone(), chords.clone()),
+        };
+        builder.build()?;
+
+        match self
+            .shortcuts
+            .iter_mut()
+            .find(|(message, _)| *message == action)
+        {
+            Some(shortcut) => shortcut.1 = chords,
+            None => self.shortcuts.push((action, chords)),
+        }
+        self.clear_pending_keys();
+        Ok(())
+    }
+
+    pub fn remove_shortcut(&mut self, action: &Message) {
+        self.shortcuts.retain(|(message, _)| message != action);
+        self.clear_pending_keys();
+    }
+
+    /// Restores the shortcuts given to `with_custom_shortcuts`
+    pub fn reset_to_defaults(&mut self) {
+        self.shortcuts = self.default_shortcuts.clone();
+        self.clear_pending_keys();
+    }
+
+    /// Writes the shortcuts to the file set with `with_shortcuts_file`, if any
+    pub fn save_shortcuts(&mut self) -> std::io::Result<()> {
+        let Some(path) = self.shortcuts_file.as_ref() else {
+            return Ok(());
+        };
+        save_shortcuts(path, &self.shortcuts)?;
+        self.shortcuts_modified = std::fs::metadata(path)?.modified().ok();
+        Ok(())
+    }
+
+    /// Pages visited in every tab, oldest first
+    pub fn history(&self) -> &[HistoryEntry] {
+        &self.history
+    }
+
+    pub fn profile(&self) -> Option<&Profile> {
+        self.profile.as_ref()
+    }
+
+    /// Origins of the sites open in a tab, whose data the engine can reach
+    pub fn open_site_origins(&self) -> Vec<String> {
+        let mut origins: Vec<String> = Vec::new();
+        let urls = self.engine.get_tabs().tabs().iter().map(|tab| tab.url());
+        for url in urls {
+            let Ok(url) = Url::parse(&url) else {
+                continue;
+            };
+            let origin = url.origin().ascii_serialization();
+            if matches!(url.scheme(), "http" | "https") && !origins.contains(&origin) {
+                origins.push(origin);
+            }
+        }
+        origins
+    }
+
+    /// Site data can only be cleared for sites open in a tab, see `BrowsingData`
+    pub fn clear_browsing_data(&mut self, data: BrowsingData) {
+        if data.open_site_data {
+            for origin in self.open_site_origins() {
+                self.engine.clear_site_data(&origin);
+            }
+        }
+        if data.history {
+            self.history.clear();
+            self.closed_tabs.clear();
+        }
+        if data.memory_cache {
+            self.engine.clear_memory_cache();
+            self.favicons.clear();
+        }
+
+        let current = self.engine.get_tabs().get_current_id();
+        if let Some(url) = self
+            .internal_pages
+            .get(&current)
+            .map(|page| page.url.clone())
+        {
+            self.navigate(&url);
+        }
+    }
+
     /// Allows creation of custom widgets that need interal info
     pub fn engine(&self) -> &Engine {
         &self.engine
@@ -203,6 +852,12 @@ impl<Engine: BrowserEngine> IcyBrowser<Engine> {
 
     fn update_engine(&mut self) {
         self.engine.do_work();
+        self.forget_left_internal_pages();
+        self.handle_popups();
+        self.handle_load_errors();
+        self.update_load_state();
+        self.record_history();
+        self.update_input_mode();
         if self.engine.has_loaded() {
             if self.engine.need_render() {
                 let (format, image_data) = self.engine.pixel_buffer();
@@ -224,38 +879,522 @@ impl<Engine: BrowserEngine> IcyBrowser<Engine> {
         }
     }
 
-    /// This is used to periodically update browserview
-    pub fn force_update(&mut self) -> Task<Message> {
-        self.engine.do_work();
-        let (format, image_data) = self.engine.pixel_buffer();
-        let view = ImageInfo::new(
-            image_data,
-            format,
-            self.view_size.width,
-            self.view_size.height,
+    fn handle_popups(&mut self) {
+        for request in self.engine.take_popup_requests() {
+            if !self.engine.get_tabs().contains(request.opener) {
+                continue;
+            }
+            match self.popup_policy {
+                PopupPolicy::Allow => {
+                    if let Some(url) = to_url(&request.url) {
+                        self.open_tab(url, Some(request.opener), true);
+                    }
+                }
+                PopupPolicy::Block | PopupPolicy::Ask => self
+                    .blocked_popups
+                    .entry(request.opener)
+                    .or_default()
+                    .push(request),
+            }
+        }
+
+        self.blocked_popups
+            .retain(|id, _| self.engine.get_tabs().contains(*id));
+        let current = self.engine.get_tabs().get_current_id();
+        self.nav_bar_state.blocked_popups = self
+            .blocked_popups
+            .get(&current)
+            .map(|popups| popups.len())
+            .unwrap_or(0);
+        self.nav_bar_state.popup_policy = self.popup_policy;
+    }
+
+    /// Starts fetching icons for loaded tabs whose site has not been seen yet
+    fn fetch_favicons(&mut self) -> Task<Message> {
+        let mut tasks = Vec::new();
+        for tab in self.engine.get_tabs().tabs() {
+            if tab.load_state() != LoadState::Loaded {
+                continue;
+            }
+            let url = tab.url();
+            if self.favicons.request(&url) {
+                if let Some(icon_url) = favicon_url(&url, tab.icon_url()) {
+                    tasks.push(Task::perform(fetch_favicon(icon_url), move |bytes| {
+                        Message::FaviconFetched(url.clone(), bytes)
+                    }));
+                }
+            }
+        }
+        Task::batch(tasks)
+    }
+
+    fn handle_load_errors(&mut self) {
+        let tabs = self.engine.get_tabs();
+        for tab in tabs.tabs() {
+            if let Some(error) = tab.take_load_error() {
+                self.load_errors.insert(tab.id(), (tab.load_count(), error));
+            }
+        }
+        // an error is stale once its tab closes or loads anything else
+        self.load_errors.retain(|id, (load_count, _)| {
+            tabs.contains(*id) && tabs.get(*id).load_count() == *load_count
+        });
+
+        let current = tabs.get_current_id();
+        if let Some((_, error)) = self.load_errors.remove(&current) {
+            // links to custom schemes fail in the engine and are retried through their handler
+            if let Some(url) = Url::parse(&error.url)
+                .ok()
+                .filter(|url| self.engine.handles_scheme(url.scheme()))
+            {
+                self.engine.goto_url(&url);
+                return;
+            }
+
+            let nonce = page_nonce();
+            self.show_internal_page(
+                error_page(&self.error_page, &error, &nonce),
+                error.url,
+                nonce,
+            );
+        }
+    }
+
+    /// Shows `html` in the current tab in place of `url`, accepting actions that carry `nonce`
+    fn show_internal_page(&mut self, html: String, url: String, nonce: String) {
+        let current = self.engine.get_tabs().get_current();
+        let (id, load_count) = (current.id(), current.load_count());
+        self.engine.goto_html(html);
+        self.nav_bar_state.url = url.clone();
+        self.internal_pages.insert(
+            id,
+            InternalPage {
+                url,
+                nonce,
+                load_count,
+            },
         );
-        self.engine.get_tabs_mut().get_current_mut().set_view(view);
+    }
+
+    /// Converts actions from internal pages into messages, ignoring any other page
+    fn handle_page_actions(&mut self) -> Task<Message> {
+        let actions = self.engine.take_page_actions();
+        Task::batch(
+            actions
+                .into_iter()
+                .filter_map(|(id, action)| {
+                    let page = self.internal_pages.get(&id)?;
+                    let action = action.strip_prefix(&page.nonce)?.strip_prefix(':')?;
+                    page_action_message(&page.url, action)
+                })
+                .map(Task::done),
+        )
+    }
+
+    /// Drops internal pages whose tab closed or started loading anything after the page itself
+    fn forget_left_internal_pages(&mut self) {
+        let tabs = self.engine.get_tabs();
+        self.internal_pages.retain(|id, page| {
+            tabs.contains(*id) && tabs.get(*id).load_count() <= page.load_count + 1
+        });
+    }
+
+    /// Forgets that the current tab shows generated html before it navigates away
+    fn leave_internal_page(&mut self) -> Option<String> {
+        let current = self.engine.get_tabs().get_current_id();
+        self.internal_pages.remove(&current).map(|page| page.url)
+    }
+
+    fn record_history(&mut self) {
+        if self.private {
+            return;
+        }
+        self.recorded_urls
+            .retain(|id, _| self.engine.get_tabs().contains(*id));
 
+        for tab in self.engine.get_tabs().tabs() {
+            if tab.load_state() != LoadState::Loaded || self.internal_pages.contains_key(&tab.id())
+            {
+                continue;
+            }
+            let url = tab.url();
+            if !(url.starts_with("http://") || url.starts_with("https://"))
+                || self.recorded_urls.get(&tab.id()) == Some(&url)
+            {
+                continue;
+            }
+            self.recorded_urls.insert(tab.id(), url.clone());
+            self.history.push(HistoryEntry {
+                url,
+                title: tab.title(),
+                visited: SystemTime::now(),
+            });
+        }
+    }
+
+    /// Loads `url` in the current tab, rendering internal about pages without the engine
+    fn navigate(&mut self, url: &str) {
+        self.leave_internal_page();
+        if ABOUT_PAGES.contains(&url) {
+            let engine = std::any::type_name::<Engine>();
+            let info = AboutInfo {
+                history: &self.history,
+                bookmarks: self.bookmarks.as_deref().unwrap_or_default(),
+                shortcuts: &self.shortcuts,
+                favicons: &self.favicons,
+                engine: engine.rsplit("::").next().unwrap_or(engine),
+            };
+            let nonce = page_nonce();
+            if let Some(html) = about_page(url, &info, &nonce) {
+                self.show_internal_page(html, url.to_string(), nonce);
+            }
+        } else if let Some(url) = to_url(url) {
+            self.goto_url(&url);
+        }
+    }
+
+    /// Loads `url` in the current tab, switching to the user agent overridden for its host first
+    fn goto_url(&mut self, url: &Url) {
+        let user_agent = self.user_agent_for(url);
+        if self.engine.get_tabs().get_current().user_agent() != user_agent {
+            self.engine.set_user_agent(user_agent.as_deref());
+        }
+        self.engine.goto_url(url);
+    }
+
+    /// The user agent overriding the engine's default for `url`'s host
+    fn user_agent_for(&self, url: &Url) -> Option<String> {
+        if !matches!(url.scheme(), "http" | "https") {
+            return None;
+        }
+        url.host_str()
+            .and_then(|host| self.user_agents.get(host))
+            .cloned()
+    }
+
+    /// Overrides the user agent for the current tab's host, or removes the override when `None`
+    ///
+    /// The page is loaded again so the site sees the new user agent
+    fn set_host_user_agent(&mut self, user_agent: Option<&str>) {
+        let current = self.engine.get_tabs().get_current_id();
+        if self.internal_pages.contains_key(&current) {
+            return;
+        }
+        let Some(url) = Url::parse(&self.engine.get_tabs().get_current().url()).ok() else {
+            return;
+        };
+        let Some(host) = url.host_str().map(String::from) else {
+            return;
+        };
+        match user_agent {
+            Some(user_agent) => self.user_agents.insert(host, user_agent.to_string()),
+            None => self.user_agents.remove(&host),
+        };
+        if self.engine.get_tabs().get_current().user_agent() != self.user_agent_for(&url) {
+            self.goto_url(&url);
+        }
+    }
+
+    /// Moves keyboard focus, text inputs that aren't the target are unfocused
+    fn set_focus(&mut self, target: FocusTarget) -> Task<Message> {
+        self.focus = target;
+        match target {
+            FocusTarget::NavBar => Task::batch([
+                text_input::focus(nav_bar::url_input_id()),
+                text_input::select_all(nav_bar::url_input_id()),
+            ]),
+            FocusTarget::FindBar => Task::batch([
+                text_input::focus(find_bar::find_input_id()),
+                text_input::select_all(find_bar::find_input_id()),
+            ]),
+            // focusing an id no widget has unfocuses every text input
+            _ => text_input::focus(text_input::Id::unique()),
+        }
+    }
+
+    /// Advances the pending shortcut sequence with a key press
+    ///
+    /// `page_event` is sent to the page unless a shortcut uses the key. Keys held by a sequence
+    /// that breaks are sent late, in the order they were pressed
+    fn handle_shortcut_key(
+        &mut self,
+        key: keyboard::Key,
+        modifiers: keyboard::Modifiers,
+        page_event: Option<keyboard::Event>,
+    ) -> Task<Message> {
+        // holding a modifier for the next chord doesn't end the sequence
+        if matches!(
+            key,
+            key::Key::Named(
+                key::Named::Control
+                    | key::Named::Shift
+                    | key::Named::Alt
+                    | key::Named::Super
+                    | key::Named::Meta
+            )
+        ) {
+            return Self::send_to_page(page_event);
+        }
+        // plain keys type into a focused field of the page instead of starting a sequence
+        if self.pending_keys.is_empty()
+            && !is_command_key(&key, &modifiers)
+            && self.engine.has_input_focus()
+        {
+            return Self::send_to_page(page_event);
+        }
+
+        let pressed = self
+            .pending_keys
+            .press(&self.shortcuts, key, modifiers, page_event);
+        let unused = Self::send_to_page(pressed.unused.into_iter().flatten());
+        match pressed.run {
+            Some(message) => unused.chain(Task::done(message)),
+            None => unused,
+        }
+    }
+
+    /// Sends key events to the page one after another
+    fn send_to_page(events: impl IntoIterator<Item = keyboard::Event>) -> Task<Message> {
+        events.into_iter().fold(Task::none(), |task, event| {
+            task.chain(Task::done(Message::SendKeyboardEvent(Some(event))))
+        })
+    }
+
+    /// Runs the normal mode command the key completes, returns `None` for keys it doesn't use
+    fn handle_normal_mode_key(&mut self, key: &keyboard::Key) -> Option<Task<Message>> {
+        let key::Key::Character(char) = key else {
+            self.normal_keys.clear();
+            return None;
+        };
+        self.normal_keys.push_str(char);
+
+        let message = match self.normal_keys.as_str() {
+            "j" => Message::ScrollDown,
+            "k" => Message::ScrollUp,
+            "d" => Message::ScrollHalfPageDown,
+            "u" => Message::ScrollHalfPageUp,
+            "gg" => Message::ScrollToTop,
+            "G" => Message::ScrollToBottom,
+            "H" => Message::GoBackward,
+            "L" => Message::GoForward,
+            ":" => Message::ShowOverlay,
+            "i" => Message::EnterInsertMode,
+            "f" => Message::ShowLinkHints,
+            "F" => Message::ShowLinkHintsInNewTab,
+            "g" => return Some(Task::none()),
+            _ => {
+                self.normal_keys.clear();
+                return None;
+            }
+        };
+        self.normal_keys.clear();
+        Some(Task::done(message))
+    }
+
+    /// Follows the page's input focus into and out of insert mode
+    fn update_input_mode(&mut self) {
+        let focused = self.engine.has_input_focus();
+        if focused == self.input_focused {
+            return;
+        }
+        self.input_focused = focused;
+        match self.input_mode {
+            Some(InputMode::Normal) if focused => self.input_mode = Some(InputMode::Insert),
+            Some(InputMode::Insert) if !focused => self.input_mode = Some(InputMode::Normal),
+            _ => (),
+        }
+    }
+
+    /// Narrows the link hints with a typed key, following the hint once its label is complete
+    fn handle_link_hint_key(&mut self, key: &keyboard::Key) -> Task<Message> {
+        let Some(state) = self.link_hints.as_mut() else {
+            return Task::none();
+        };
+        let key::Key::Character(char) = key else {
+            return match key {
+                key::Key::Named(key::Named::Escape) => Task::done(Message::HideLinkHints),
+                _ => Task::none(),
+            };
+        };
+        state.typed.push_str(&char.to_lowercase());
+
+        if let Some(hint) = state.selected() {
+            let element = hint.element.clone();
+            let new_tab = state.new_tab;
+            self.link_hints = None;
+            return match (new_tab, element.url) {
+                (true, Some(url)) => Task::done(Message::OpenInNewTab(url)),
+                _ => {
+                    self.click_at(element.bounds.center());
+                    Task::none()
+                }
+            };
+        }
+        if state.visible().next().is_none() {
+            self.link_hints = None;
+        }
         Task::none()
     }
 
+    fn click_at(&mut self, point: Point) {
+        for event in [
+            mouse::Event::CursorMoved { position: point },
+            mouse::Event::ButtonPressed(mouse::Button::Left),
+            mouse::Event::ButtonReleased(mouse::Button::Left),
+        ] {
+            self.engine.handle_mouse_event(point, event);
+        }
+    }
+
+    fn scroll_by(&self, pixels: f32) {
+        // positive deltas scroll up like a mouse wheel
+        self.engine
+            .scroll(mouse::ScrollDelta::Pixels { x: 0.0, y: -pixels });
+    }
+
+    /// Loads the shortcuts file if it changed since it was last read
+    fn reload_shortcuts(&mut self) {
+        self.shortcuts_checked = Instant::now();
+        let Some(path) = self.shortcuts_file.as_ref() else {
+            return;
+        };
+        let modified = std::fs::metadata(path)
+            .and_then(|meta| meta.modified())
+            .ok();
+        if modified.is_some() && modified == self.shortcuts_modified {
+            return;
+        }
+        self.shortcuts_modified = modified;
+
+        match load_shortcuts(path) {
+            Ok(shortcuts) => {
+                self.shortcuts = shortcuts;
+                self.shortcuts_error = None;
+                self.clear_pending_keys();
+            }
+            Err(error) => self.shortcuts_error = Some(error),
+        }
+    }
+
+    /// Ends a sequence nobody finished in time, its keys reach the page after all
+    fn expire_pending_keys(&mut self) -> Task<Message> {
+        Self::send_to_page(
+            self.pending_keys
+                .expire(self.chord_timeout)
+                .into_iter()
+                .flatten(),
+        )
+    }
+
+    fn clear_pending_keys(&mut self) {
+        self.pending_keys.take();
+    }
+
+    fn update_load_state(&mut self) {
+        self.nav_bar_state.load_state = self.engine.get_tabs().get_current().load_state();
+        self.nav_bar_state.private = self.private;
+    }
+
+    /// Creates a tab for `url`, placing it next to `opener` when it was spawned from another tab
+    ///
+    /// Background tabs load without taking focus from the current tab
+    fn open_tab(&mut self, url: Url, opener: Option<u32>, focus: bool) -> u32 {
+        let bounds = self.view_size;
+        let user_agent = self.user_agent_for(&url);
+        let tab = self.engine.new_tab(
+            url.clone(),
+            Size::new(bounds.width + 10, bounds.height - 10),
+            user_agent.as_deref(),
+        );
+        let id = match opener {
+            Some(opener) => self.engine.get_tabs_mut().insert_with_opener(tab, opener),
+            None => self.engine.get_tabs_mut().insert(tab),
+        };
+        self.engine.resize(bounds);
+        if focus {
+            self.engine.get_tabs_mut().set_current_id(id);
+            self.engine.force_need_render();
+            self.engine.goto_url(&url);
+            self.nav_bar_state.url = url.to_string();
+        }
+        id
+    }
+
+    /// This is used to periodically update browserview
+    ///
+    /// Runs on every `Message::Update` tick, other messages leave the engine to the next tick
+    pub fn force_update(&mut self) -> Task<Message> {
+        self.update_engine();
+        Task::batch([self.fetch_favicons(), self.handle_page_actions()])
+    }
+
     /// the update method which is required by iced for widgets
     pub fn update(&mut self, event: Message) -> Task<Message> {
-        let task = match event {
-            Message::Update => self.force_update(),
+        match event {
+            Message::Update => {
+                let expired = self.expire_pending_keys();
+                if self.shortcuts_checked.elapsed() > Self::SHORTCUTS_FILE_POLL {
+                    self.reload_shortcuts();
+                }
+                Task::batch([expired, self.force_update()])
+            }
             Message::UpdateViewSize(size) => {
                 self.view_size = size;
                 self.engine.resize(size);
                 Task::none()
             }
             Message::SendKeyboardEvent(event) => {
+                // outside of insert mode keys are commands, not input for the page
+                if self.focus != FocusTarget::Page
+                    || self.link_hints.is_some()
+                    || matches!(
+                        self.input_mode,
+                        Some(InputMode::Normal | InputMode::Command)
+                    )
+                {
+                    return Task::none();
+                }
                 self.engine
                     .handle_keyboard_event(event.expect("Value cannot be none"));
                 Task::none()
             }
+            Message::PageKeyboardEvent(event) => {
+                if self.events_subscribed {
+                    return Task::none();
+                }
+                self.update(Message::SendKeyboardEvent(event))
+            }
             Message::SendMouseEvent(point, event) => {
-                self.engine
-                    .handle_mouse_event(point, event.expect("Value cannot be none"));
+                let event = event.expect("Value cannot be none");
+                if let mouse::Event::ButtonPressed(_) = event {
+                    self.link_hints = None;
+                    self.focus = FocusTarget::Page;
+                }
+                match event {
+                    mouse::Event::ButtonPressed(mouse::Button::Back) => {
+                        return Task::done(Message::GoBackward)
+                    }
+                    mouse::Event::ButtonPressed(mouse::Button::Forward) => {
+                        return Task::done(Message::GoForward)
+                    }
+                    // middle click or ctrl click on a link opens it in the background
+                    mouse::Event::ButtonPressed(
+                        button @ (mouse::Button::Middle | mouse::Button::Left),
+                    ) if button == mouse::Button::Middle || self.modifiers.command() => {
+                        if let Some(url) = self.engine.hovered_link() {
+                            self.swallowed_button = Some(button);
+                            return Task::done(Message::OpenInBackgroundTab(url));
+                        }
+                    }
+                    mouse::Event::ButtonReleased(button)
+                        if self.swallowed_button == Some(button) =>
+                    {
+                        self.swallowed_button = None;
+                        return Task::none();
+                    }
+                    _ => (),
+                }
+                self.engine.handle_mouse_event(point, event);
                 Task::none()
             }
             Message::ChangeTab(index_type) => {
@@ -264,7 +1403,7 @@ impl<Engine: BrowserEngine> IcyBrowser<Engine> {
                     TabSelectionType::Index(index) => self.engine.get_tabs().index_to_id(index),
                 };
                 self.engine.get_tabs_mut().set_current_id(id);
-                self.nav_bar_state.0 = self.engine.get_tabs().get_current().url();
+                self.nav_bar_state.url = self.engine.get_tabs().get_current().url();
                 Task::none()
             }
             Message::CloseCurrentTab => Task::done(Message::CloseTab(TabSelectionType::Id(
@@ -272,7 +1411,13 @@ impl<Engine: BrowserEngine> IcyBrowser<Engine> {
             ))),
             Message::CloseTab(index_type) => {
                 // ensure there is always at least one tab
-                if self.engine.get_tabs().tabs().len() == 1 {
+                let last_tab = self.engine.get_tabs().tabs().len() == 1;
+                if last_tab {
+                    if self.private {
+                        // only data the open tab reaches can be cleared, so before it closes, the
+                        // rest lives until the engine is dropped
+                        self.clear_browsing_data(BrowsingData::ALL);
+                    }
                     let _ = self.update(Message::CreateTab); // ignore task
                 }
 
@@ -280,53 +1425,188 @@ impl<Engine: BrowserEngine> IcyBrowser<Engine> {
                     TabSelectionType::Id(id) => id,
                     TabSelectionType::Index(index) => self.engine.get_tabs().index_to_id(index),
                 };
+                let url = match self.internal_pages.get(&id) {
+                    Some(page) => page.url.clone(),
+                    None => self.engine.get_tabs().get(id).url(),
+                };
+                // a private session ends with its last tab, so nothing of it is kept to reopen
+                if !url.is_empty() && !(last_tab && self.private) {
+                    self.closed_tabs.push(url);
+                }
+
                 self.engine.get_tabs_mut().remove(id);
-                self.nav_bar_state.0 = self.engine.get_tabs().get_current().url();
+                self.nav_bar_state.url = self.engine.get_tabs().get_current().url();
                 Task::none()
             }
             Message::CreateTab => {
-                self.nav_bar_state.0 = self.home.to_string();
-                let home = self.home.clone();
-                let bounds = self.view_size;
-                let tab = self.engine.new_tab(
-                    home.clone(),
-                    Size::new(bounds.width + 10, bounds.height - 10),
-                );
-                let id = self.engine.get_tabs_mut().insert(tab);
-                self.engine.get_tabs_mut().set_current_id(id);
-                self.engine.force_need_render();
-                self.engine.resize(bounds);
-                self.engine.goto_url(&home);
+                self.open_tab(self.home.clone(), None, true);
+                Task::none()
+            }
+            Message::ReopenClosedTab => {
+                if let Some(url) = self.closed_tabs.pop() {
+                    if ABOUT_PAGES.contains(&url.as_str()) {
+                        self.open_tab(Url::parse("about:blank").unwrap(), None, true);
+                        self.navigate(&url);
+                    } else if let Some(url) = to_url(&url) {
+                        self.open_tab(url, None, true);
+                    }
+                }
+                Task::none()
+            }
+            Message::NextTab | Message::PreviousTab => {
+                let tabs = self.engine.get_tabs();
+                let count = tabs.tabs().len();
+                let index = tabs.id_to_index(tabs.get_current_id());
+                let index = match event {
+                    Message::NextTab => (index + 1) % count,
+                    _ => (index + count - 1) % count,
+                };
+                Task::done(Message::ChangeTab(TabSelectionType::Index(index)))
+            }
+            Message::SelectTab1
+            | Message::SelectTab2
+            | Message::SelectTab3
+            | Message::SelectTab4
+            | Message::SelectTab5
+            | Message::SelectTab6
+            | Message::SelectTab7
+            | Message::SelectTab8
+            | Message::SelectTab9 => {
+                let index = match event {
+                    Message::SelectTab1 => 0,
+                    Message::SelectTab2 => 1,
+                    Message::SelectTab3 => 2,
+                    Message::SelectTab4 => 3,
+                    Message::SelectTab5 => 4,
+                    Message::SelectTab6 => 5,
+                    Message::SelectTab7 => 6,
+                    Message::SelectTab8 => 7,
+                    _ => 8,
+                };
+                if index < self.engine.get_tabs().tabs().len() {
+                    Task::done(Message::ChangeTab(TabSelectionType::Index(index)))
+                } else {
+                    Task::none()
+                }
+            }
+            Message::OpenInNewTab(url) => {
+                let opener = self.engine.get_tabs().get_current_id();
+                if ABOUT_PAGES.contains(&url.as_str()) {
+                    self.open_tab(Url::parse("about:blank").unwrap(), Some(opener), true);
+                    self.navigate(&url);
+                } else if let Some(url) = to_url(&url) {
+                    self.open_tab(url, Some(opener), true);
+                }
+                Task::none()
+            }
+            Message::OpenInBackgroundTab(url) => {
+                if let Some(url) = to_url(&url) {
+                    let opener = self.engine.get_tabs().get_current_id();
+                    self.open_tab(url, Some(opener), false);
+                }
                 Task::none()
             }
             Message::GoBackward => {
+                self.leave_internal_page();
                 self.engine.go_back();
-                self.nav_bar_state.0 = self.engine.get_tabs().get_current().url();
+                self.nav_bar_state.url = self.engine.get_tabs().get_current().url();
                 Task::none()
             }
             Message::GoForward => {
+                self.leave_internal_page();
                 self.engine.go_forward();
-                self.nav_bar_state.0 = self.engine.get_tabs().get_current().url();
+                self.nav_bar_state.url = self.engine.get_tabs().get_current().url();
                 Task::none()
             }
             Message::Refresh => {
-                self.engine.refresh();
+                // internal pages are regenerated and error pages retry the url that failed
+                match self.leave_internal_page() {
+                    Some(url) => self.navigate(&url),
+                    None => self.engine.refresh(),
+                }
+                Task::none()
+            }
+            Message::Stop => {
+                self.engine.stop();
                 Task::none()
             }
             Message::GoHome => {
-                self.engine.goto_url(&self.home);
+                self.leave_internal_page();
+                self.goto_url(&self.home.clone());
                 Task::none()
             }
             Message::GoToUrl(url) => {
-                self.engine.goto_url(&to_url(&url).unwrap());
-                Task::none()
+                self.navigate(&url);
+                // the loaded page takes over from the address bar
+                if self.focus == FocusTarget::NavBar {
+                    self.set_focus(FocusTarget::Page)
+                } else {
+                    Task::none()
+                }
             }
             Message::UpdateUrl => {
-                self.nav_bar_state.0 = self.engine.get_tabs().get_current().url();
+                self.nav_bar_state.url = self.engine.get_tabs().get_current().url();
                 Task::none()
             }
             Message::UrlChanged(url) => {
-                self.nav_bar_state.0 = url;
+                self.focus = FocusTarget::NavBar;
+                self.nav_bar_state.url = url;
+                Task::none()
+            }
+            Message::AllowBlockedPopups => {
+                let current = self.engine.get_tabs().get_current_id();
+                if let Some(popups) = self.blocked_popups.remove(&current) {
+                    for popup in popups {
+                        if let Some(url) = to_url(&popup.url) {
+                            self.open_tab(url, Some(current), true);
+                        }
+                    }
+                }
+                Task::none()
+            }
+            Message::DismissBlockedPopups => {
+                let current = self.engine.get_tabs().get_current_id();
+                self.blocked_popups.remove(&current);
+                Task::none()
+            }
+            Message::FaviconFetched(url, bytes) => {
+                if let Some(bytes) = bytes {
+                    self.favicons.insert(&url, &bytes);
+                }
+                Task::none()
+            }
+            Message::ClearHistory => {
+                self.clear_browsing_data(BrowsingData {
+                    history: true,
+                    open_site_data: false,
+                    memory_cache: false,
+                });
+                Task::none()
+            }
+            Message::ClearOpenSiteData => {
+                self.clear_browsing_data(BrowsingData {
+                    history: false,
+                    open_site_data: true,
+                    memory_cache: false,
+                });
+                Task::none()
+            }
+            Message::ClearMemoryCache => {
+                self.clear_browsing_data(BrowsingData {
+                    history: false,
+                    open_site_data: false,
+                    memory_cache: true,
+                });
+                Task::none()
+            }
+            Message::ClearBrowsingData => {
+                self.clear_browsing_data(BrowsingData::ALL);
+                Task::none()
+            }
+            Message::ClearSiteDataOnNextStart => {
+                if let Some(profile) = &self.profile {
+                    let _ = profile.clear_engine_data_on_start();
+                }
                 Task::none()
             }
             Message::ToggleTabBar => {
@@ -365,6 +1645,183 @@ impl<Engine: BrowserEngine> IcyBrowser<Engine> {
                 self.with_bookmark_bar = false;
                 Task::none()
             }
+            Message::ToggleStatusBar => {
+                self.with_status_bar = !self.with_status_bar;
+                Task::none()
+            }
+            Message::ZoomIn => {
+                let zoom = (self.engine.zoom_level() + Self::ZOOM_STEP).min(Self::ZOOM_MAX);
+                self.engine.set_zoom_level(zoom);
+                Task::none()
+            }
+            Message::ZoomOut => {
+                let zoom = (self.engine.zoom_level() - Self::ZOOM_STEP).max(Self::ZOOM_MIN);
+                self.engine.set_zoom_level(zoom);
+                Task::none()
+            }
+            Message::ResetZoom => {
+                self.engine.set_zoom_level(1.0);
+                Task::none()
+            }
+            Message::ScrollDown => {
+                self.scroll_by(Self::SCROLL_STEP);
+                Task::none()
+            }
+            Message::ScrollUp => {
+                self.scroll_by(-Self::SCROLL_STEP);
+                Task::none()
+            }
+            Message::ScrollHalfPageDown => {
+                self.scroll_by(self.view_size.height as f32 / 2.0);
+                Task::none()
+            }
+            Message::ScrollHalfPageUp => {
+                self.scroll_by(-(self.view_size.height as f32 / 2.0));
+                Task::none()
+            }
+            // further than any page is long, the engine stops at the edge
+            Message::ScrollToTop => {
+                self.scroll_by(-1_000_000.0);
+                Task::none()
+            }
+            Message::ScrollToBottom => {
+                self.scroll_by(1_000_000.0);
+                Task::none()
+            }
+            Message::FocusAddressBar => self.set_focus(FocusTarget::NavBar),
+            Message::FocusPage => {
+                self.nav_bar_state.url = self.engine.get_tabs().get_current().url();
+                self.set_focus(FocusTarget::Page)
+            }
+            Message::ShowFindBar => {
+                self.find_bar.get_or_insert_with(FindBarState::default);
+                self.set_focus(FocusTarget::FindBar)
+            }
+            Message::HideFindBar => {
+                self.find_bar = None;
+                self.set_focus(FocusTarget::Page)
+            }
+            Message::FindQueryChanged(query) => {
+                self.focus = FocusTarget::FindBar;
+                if let Some(state) = self.find_bar.as_mut() {
+                    state.no_matches =
+                        !query.is_empty() && !self.engine.find_in_page(&query, false);
+                    state.query = query;
+                }
+                Task::none()
+            }
+            Message::FindNext | Message::FindPrevious => {
+                if let Some(state) = self.find_bar.as_mut() {
+                    if !state.query.is_empty() {
+                        let backwards = event == Message::FindPrevious;
+                        state.no_matches = !self.engine.find_in_page(&state.query, backwards);
+                    }
+                }
+                Task::none()
+            }
+            Message::ShowShortcutHelp => {
+                self.show_shortcut_help = true;
+                self.set_focus(FocusTarget::Panel)
+            }
+            Message::HideShortcutHelp => {
+                self.show_shortcut_help = false;
+                self.set_focus(FocusTarget::Page)
+            }
+            Message::ShowShortcutEditor => {
+                self.shortcut_editor = Some(ShortcutEditorState::new());
+                self.set_focus(FocusTarget::Panel)
+            }
+            Message::HideShortcutEditor => {
+                self.shortcut_editor = None;
+                self.set_focus(FocusTarget::Page)
+            }
+            Message::ShortcutEditorSelect(command) => {
+                if let Some(editor) = self.shortcut_editor.as_mut() {
+                    editor.select(*command);
+                }
+                Task::none()
+            }
+            Message::ShortcutEditorCancel => {
+                if let Some(editor) = self.shortcut_editor.as_mut() {
+                    editor.cancel();
+                }
+                Task::none()
+            }
+            Message::ShortcutEditorApply => {
+                if let Some(mut editor) = self.shortcut_editor.take() {
+                    if let Some(action) = editor.selected.clone() {
+                        let chords = std::mem::take(&mut editor.captured);
+                        editor.status = self
+                            .set_shortcut(action, chords)
+                            .err()
+                            .map(|error| error.to_string());
+                        editor.selected = None;
+                    }
+                    self.shortcut_editor = Some(editor);
+                }
+                Task::none()
+            }
+            Message::ShortcutEditorRemove(command) => {
+                self.remove_shortcut(&command);
+                Task::none()
+            }
+            Message::ShortcutEditorReset => {
+                self.reset_to_defaults();
+                if let Some(editor) = self.shortcut_editor.as_mut() {
+                    editor.status = Some("Restored the default shortcuts".to_string());
+                }
+                Task::none()
+            }
+            Message::ShortcutEditorSave => {
+                let status = if self.shortcuts_file.is_none() {
+                    "There is no shortcuts file to save to".to_string()
+                } else {
+                    match self.save_shortcuts() {
+                        Ok(()) => "Saved".to_string(),
+                        Err(error) => format!("Failed to save shortcuts: {}", error),
+                    }
+                };
+                if let Some(editor) = self.shortcut_editor.as_mut() {
+                    editor.status = Some(status);
+                }
+                Task::none()
+            }
+            Message::ShowLinkHints => {
+                self.link_hints = Some(LinkHintState::new(self.engine.clickable_elements(), false));
+                Task::none()
+            }
+            Message::ShowLinkHintsInNewTab => {
+                self.link_hints = Some(LinkHintState::new(self.engine.clickable_elements(), true));
+                Task::none()
+            }
+            Message::HideLinkHints => {
+                self.link_hints = None;
+                Task::none()
+            }
+            Message::EnterNormalMode => {
+                if self.input_mode.is_some() {
+                    self.input_mode = Some(InputMode::Normal);
+                }
+                Task::none()
+            }
+            Message::EnterInsertMode => {
+                if self.input_mode.is_some() {
+                    self.input_mode = Some(InputMode::Insert);
+                }
+                Task::none()
+            }
+            Message::RequestDesktopSite => {
+                self.set_host_user_agent(Some(DESKTOP_USER_AGENT));
+                Task::none()
+            }
+            Message::UseMobileUserAgent => {
+                self.set_host_user_agent(Some(MOBILE_USER_AGENT));
+                Task::none()
+            }
+            Message::UseDefaultUserAgent => {
+                self.set_host_user_agent(None);
+                Task::none()
+            }
             Message::CommandPaletteQueryChanged => {
                 self.command_palette_state.filtered_results =
                     self.command_palette_state.possible_results.clone();
@@ -514,15 +1971,28 @@ impl<Engine: BrowserEngine> IcyBrowser<Engine> {
             }
             Message::ShowOverlay => {
                 self.show_overlay = true;
+                self.focus = FocusTarget::CommandPalette;
+                if self.input_mode.is_some() {
+                    self.input_mode = Some(InputMode::Command);
+                }
                 widget::focus_next()
             }
             Message::HideOverlay => {
                 self.show_overlay = false;
-                widget::focus_next()
+                if self.input_mode == Some(InputMode::Command) {
+                    self.input_mode = Some(InputMode::Normal);
+                }
+                self.set_focus(FocusTarget::Page)
             }
-            Message::IcedEvent(event) => {
+            Message::IcedEvent(event, captured) => {
+                self.events_subscribed = true;
                 match event {
-                    Some(Event::Keyboard(key)) => {
+                    // keys a focused text input captured still reach escape handling and
+                    // shortcuts, but never the page
+                    Some(Event::Keyboard(event)) => {
+                        if let keyboard::Event::ModifiersChanged(modifiers) = event {
+                            self.modifiers = modifiers;
+                        }
                         if let iced::keyboard::Event::KeyPressed {
                             key,
                             modified_key: _,
@@ -530,45 +2000,138 @@ impl<Engine: BrowserEngine> IcyBrowser<Engine> {
                             location: _,
                             modifiers,
                             text: _,
-                        } = key
+                        } = event.clone()
                         {
+                            if captured {
+                                // typing into a text input isn't a command
+                                if key != keyboard::Key::Named(key::Named::Escape)
+                                    && !is_command_key(&key, &modifiers)
+                                {
+                                    return Task::none();
+                                }
+                            } else if matches!(
+                                self.focus,
+                                FocusTarget::NavBar | FocusTarget::FindBar
+                            ) && !matches!(
+                                key,
+                                key::Key::Named(
+                                    key::Named::Tab | key::Named::ArrowUp | key::Named::ArrowDown
+                                )
+                            ) {
+                                // a focused text input captures every other key, so it lost focus
+                                self.focus = FocusTarget::Page;
+                            }
+
                             // Default behaviors
+                            // the shortcut editor captures every key for the selected command,
+                            // applying and cancelling are left to its buttons so any key can be
+                            // bound, and no shortcut runs while it is open
+                            if let Some(editor) = self.shortcut_editor.as_mut() {
+                                if editor.selected.is_some() {
+                                    return match key {
+                                        key::Key::Named(
+                                            key::Named::Control
+                                            | key::Named::Shift
+                                            | key::Named::Alt
+                                            | key::Named::Super
+                                            | key::Named::Meta,
+                                        ) => Task::none(),
+                                        key => {
+                                            editor
+                                                .captured
+                                                .push(keypress_to_chord(&key, &modifiers));
+                                            Task::none()
+                                        }
+                                    };
+                                } else if key == keyboard::Key::Named(key::Named::Escape) {
+                                    return Task::done(Message::HideShortcutEditor);
+                                }
+                                return Task::none();
+                            }
                             // escape to exit command palette
                             if self.show_overlay && key == keyboard::Key::Named(key::Named::Escape)
                             {
                                 return Task::done(Message::HideOverlay);
                             }
-                            // ctrl + R = refresh
-                            else if modifiers.control() && key == key::Key::Character("r".into())
+                            // escape returns from the address bar to the page
+                            else if self.focus == FocusTarget::NavBar
+                                && key == keyboard::Key::Named(key::Named::Escape)
                             {
-                                return Task::done(Message::Refresh);
+                                return Task::done(Message::FocusPage);
+                            }
+                            // escape closes the find bar
+                            else if self.find_bar.is_some()
+                                && key == keyboard::Key::Named(key::Named::Escape)
+                            {
+                                return Task::done(Message::HideFindBar);
+                            }
+                            // escape closes the shortcut help
+                            else if self.show_shortcut_help
+                                && key == keyboard::Key::Named(key::Named::Escape)
+                            {
+                                return Task::done(Message::HideShortcutHelp);
+                            }
+                            // typed keys pick a link hint
+                            else if self.link_hints.is_some() {
+                                return self.handle_link_hint_key(&key);
+                            }
+                            // escape leaves insert mode
+                            else if self.input_mode == Some(InputMode::Insert)
+                                && key == keyboard::Key::Named(key::Named::Escape)
+                            {
+                                return Task::done(Message::EnterNormalMode);
                             }
 
-                            // Shortcut (Customizable) behaviors
-                            for shortcut in self.shortcuts.iter() {
-                                if shortcut_pressed(shortcut, &key, &modifiers) {
-                                    return Task::done(shortcut.0.clone());
+                            // the command palette and panels handle their own keys
+                            if matches!(
+                                self.focus,
+                                FocusTarget::CommandPalette | FocusTarget::Panel
+                            ) {
+                                return Task::none();
+                            }
+
+                            // plain keys are normal mode commands, shortcuts still use modifiers
+                            if self.input_mode == Some(InputMode::Normal)
+                                && !(modifiers.control() || modifiers.alt() || modifiers.logo())
+                                && self.pending_keys.is_empty()
+                            {
+                                if let Some(task) = self.handle_normal_mode_key(&key) {
+                                    return task;
                                 }
                             }
+
+                            // Shortcut (Customizable) behaviors, keys a shortcut uses are consumed
+                            let page_event =
+                                (self.focus == FocusTarget::Page && !captured).then_some(event);
+                            return self.handle_shortcut_key(key, modifiers, page_event);
+                        }
+
+                        if self.focus == FocusTarget::Page && !captured {
+                            Task::done(Message::SendKeyboardEvent(Some(event)))
+                        } else {
+                            Task::none()
                         }
-                        Task::none()
                     }
                     // Other unwatched events
                     _ => Task::none(),
                 }
             }
-        };
-
-        self.update_engine();
-
-        task
+        }
     }
 
+    /// The browser's widgets
+    ///
+    /// Keys reach shortcuts through `IcyBrowser::subscription`, which embedders have to batch
+    /// into their own. Without it the page view still sends typing to the page
     pub fn view(&self) -> Element<Message> {
         let mut column = column![];
 
         if self.with_tab_bar {
-            column = column.push(tab_bar(self.engine.get_tabs()))
+            column = column.push(tab_bar(
+                self.engine.get_tabs(),
+                &self.favicons,
+                self.private,
+            ))
         }
         if self.with_nav_bar {
             column = column
@@ -576,13 +2139,46 @@ impl<Engine: BrowserEngine> IcyBrowser<Engine> {
         }
         if self.with_bookmark_bar {
             if let Some(bookmarks) = self.bookmarks.as_ref() {
-                column = column.push(bookmark_bar(bookmarks))
+                column = column.push(bookmark_bar(bookmarks, &self.favicons))
             }
         }
+        if let Some(state) = self.find_bar.as_ref() {
+            column = column.push(find_bar(state))
+        }
 
-        let browser_view = browser_view(self.engine.get_tabs().get_current().get_view());
+        let current = self.engine.get_tabs().get_current();
+        let mut browser_view = browser_view(current.get_view());
+        if self.with_status_bar {
+            let state = StatusBarState {
+                hovered_link: current.hovered_link(),
+                tooltip: current.tooltip(),
+                load_progress: current.load_state().progress(),
+                zoom: self.engine.zoom_level(),
+            };
+            browser_view = status_bar(browser_view, state);
+        }
+        let pending: Vec<String> = self
+            .pending_keys
+            .keys()
+            .map(|(key, modifiers)| keypress_to_string(key, modifiers))
+            .collect();
+        browser_view = key_indicator(browser_view, self.input_mode, &pending);
+        if let Some(state) = self.link_hints.as_ref() {
+            browser_view = link_hints(browser_view, state);
+        }
+        if self.show_shortcut_help {
+            browser_view = shortcut_help(browser_view, &self.shortcuts);
+        }
+        if let Some(state) = self.shortcut_editor.as_ref() {
+            browser_view = shortcut_editor(browser_view, state, &self.shortcuts);
+        }
         if self.show_overlay {
-            column = column.push(command_palette(browser_view, &self.command_palette_state))
+            column = column.push(command_palette(
+                browser_view,
+                &self.command_palette_state,
+                &self.favicons,
+                &self.shortcuts,
+            ))
         } else {
             column = column.push(browser_view);
         }
@@ -590,10 +2186,16 @@ impl<Engine: BrowserEngine> IcyBrowser<Engine> {
         column.into()
     }
 
+    /// Updates the engine and routes keyboard input, see `IcyBrowser::view`
     pub fn subscription(&self) -> Subscription<Message> {
         Subscription::batch([
             iced::time::every(Duration::from_millis(10)).map(move |_| Message::Update),
-            iced::event::listen().map(|e: iced::Event| Message::IcedEvent(Some(e))),
+            iced::event::listen_with(|event, status, _window| {
+                Some(Message::IcedEvent(
+                    Some(event),
+                    status == iced::event::Status::Captured,
+                ))
+            }),
         ])
     }
 }
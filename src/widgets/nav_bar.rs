@@ -1,15 +1,33 @@
-use iced::widget::{row, text::LineHeight, text_input, tooltip, tooltip::Position, Button, Space};
+use iced::widget::{
+    column, progress_bar, row, text, text::LineHeight, text_input, tooltip, tooltip::Position,
+    Button, Space,
+};
 use iced::{Element, Length};
 use iced_aw::core::icons::bootstrap::{icon_to_text, Bootstrap};
 
-use super::Message;
+use super::{Message, PopupPolicy};
+use crate::LoadState;
 
 /// Holds the state of infomation in nav_bar
-pub struct NavBarState(pub String);
+pub struct NavBarState {
+    pub url: String,
+    /// Number of popups blocked in the current tab
+    pub blocked_popups: usize,
+    pub popup_policy: PopupPolicy,
+    pub load_state: LoadState,
+    /// Shows the private browsing indicator
+    pub private: bool,
+}
 
 impl NavBarState {
     pub fn new() -> Self {
-        NavBarState(String::new())
+        NavBarState {
+            url: String::new(),
+            blocked_popups: 0,
+            popup_policy: PopupPolicy::default(),
+            load_state: LoadState::default(),
+            private: false,
+        }
     }
 }
 
@@ -19,6 +37,11 @@ impl Default for NavBarState {
     }
 }
 
+/// Id of the address bar, used to focus it
+pub fn url_input_id() -> text_input::Id {
+    text_input::Id::new("nav_bar_url")
+}
+
 /// Creates Navigation bar widget
 pub fn nav_bar(state: &NavBarState) -> Element<Message> {
     let back = tooltip_helper(
@@ -39,30 +62,81 @@ pub fn nav_bar(state: &NavBarState) -> Element<Message> {
             .into(),
         "Go Home",
     );
-    let refresh = tooltip_helper(
-        Button::new(icon_to_text(Bootstrap::ArrowCounterclockwise))
-            .on_press(Message::Refresh)
-            .into(),
-        "Refresh",
-    );
+    let refresh = if state.load_state.is_loading() {
+        tooltip_helper(
+            Button::new(icon_to_text(Bootstrap::XLg))
+                .on_press(Message::Stop)
+                .into(),
+            "Stop",
+        )
+    } else {
+        tooltip_helper(
+            Button::new(icon_to_text(Bootstrap::ArrowCounterclockwise))
+                .on_press(Message::Refresh)
+                .into(),
+            "Refresh",
+        )
+    };
     let space_left = Space::new(Length::Fill, Length::Shrink);
     let space_right = Space::new(Length::Fill, Length::Shrink);
-    let search = text_input("https://site.com", &state.0)
+    let search = text_input("https://site.com", &state.url)
+        .id(url_input_id())
         .on_input(Message::UrlChanged)
         .on_paste(Message::GoToUrl)
-        .on_submit(Message::GoToUrl(state.0.to_string()))
+        .on_submit(Message::GoToUrl(state.url.to_string()))
         .line_height(LineHeight::Relative(2.0));
 
-    row!(
+    let mut row = row![];
+    if state.private {
+        row = row.push(tooltip_helper(
+            icon_to_text(Bootstrap::Incognito).into(),
+            "Private browsing",
+        ));
+    }
+    row = row.extend([
         back,
         forward,
         home,
         refresh,
-        space_left,
-        search,
-        space_right
-    )
-    .into()
+        space_left.into(),
+        search.into(),
+        space_right.into(),
+    ]);
+
+    if state.blocked_popups > 0 {
+        row = row.push(popup_indicator(state));
+    }
+
+    match state.load_state.progress() {
+        Some(progress) => column![row, progress_bar(0.0..=1.0, progress).height(2)].into(),
+        None => row.into(),
+    }
+}
+
+fn popup_indicator(state: &NavBarState) -> Element<Message> {
+    let blocked = tooltip_helper(
+        Button::new(row!(
+            icon_to_text(Bootstrap::WindowX),
+            text(state.blocked_popups)
+        ))
+        .on_press(Message::DismissBlockedPopups)
+        .into(),
+        "Popups blocked",
+    );
+
+    match state.popup_policy {
+        PopupPolicy::Ask => row!(
+            tooltip_helper(
+                Button::new(icon_to_text(Bootstrap::WindowPlus))
+                    .on_press(Message::AllowBlockedPopups)
+                    .into(),
+                "Allow popups",
+            ),
+            blocked
+        )
+        .into(),
+        _ => blocked,
+    }
 }
 
 fn tooltip_helper<'a, Message: 'a>(
@@ -0,0 +1,169 @@
+use iced::widget::{
+    button, center, column, container, mouse_area, opaque, row, scrollable, stack, text,
+};
+use iced::widget::{Column, Space};
+use iced::{border, Alignment, Color, Element, Length, Theme};
+
+use super::shortcut_help::shortcut_keys_to_string;
+use super::Message;
+use crate::{find_conflicts, Chord, Shortcuts};
+
+/// The command being rebound and the keys captured for it so far
+#[derive(Default)]
+pub struct ShortcutEditorState {
+    pub selected: Option<Message>,
+    pub captured: Vec<Chord>,
+    /// Result of the last apply or save
+    pub status: Option<String>,
+}
+
+impl ShortcutEditorState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Starts capturing keys for `command`
+    pub fn select(&mut self, command: Message) {
+        self.selected = Some(command);
+        self.captured.clear();
+        self.status = None;
+    }
+
+    pub fn cancel(&mut self) {
+        self.selected = None;
+        self.captured.clear();
+    }
+
+    /// Commands whose shortcuts clash with the captured keys
+    pub fn conflicts(&self, shortcuts: &Shortcuts) -> Vec<Message> {
+        match self.selected.as_ref() {
+            Some(selected) => find_conflicts(shortcuts, selected, &self.captured),
+            None => Vec::new(),
+        }
+    }
+}
+
+/// Creates an overlay on `base` to rebind any command by pressing its new keys
+pub fn shortcut_editor<'a>(
+    base: impl Into<Element<'a, Message>>,
+    state: &ShortcutEditorState,
+    shortcuts: &Shortcuts,
+) -> Element<'a, Message> {
+    let conflicts = state.conflicts(shortcuts);
+
+    let mut list: Vec<Element<Message>> = Vec::new();
+    for command in Message::commands() {
+        let bound = shortcuts.iter().find(|(message, _)| *message == command);
+        let selected = state.selected.as_ref() == Some(&command);
+
+        let keys = if selected {
+            if state.captured.is_empty() {
+                "Press keys...".to_string()
+            } else {
+                shortcut_keys_to_string(&state.captured)
+            }
+        } else {
+            bound
+                .map(|(_, chords)| shortcut_keys_to_string(chords))
+                .unwrap_or_default()
+        };
+        let mut keys = text(keys).size(14);
+        if conflicts.contains(&command) {
+            keys = keys.style(text::danger);
+        }
+
+        let style: fn(&Theme, button::Status) -> button::Style = if selected {
+            button::primary
+        } else {
+            button::text
+        };
+        let mut item = row![
+            button(text(command.to_string()).size(16))
+                .style(style)
+                .on_press(Message::ShortcutEditorSelect(Box::new(command.clone()))),
+            Space::with_width(Length::Fill),
+            keys,
+        ]
+        .spacing(5)
+        .align_y(Alignment::Center);
+        if bound.is_some() && !selected {
+            item = item.push(
+                button(text("Remove").size(14))
+                    .style(button::text)
+                    .on_press(Message::ShortcutEditorRemove(Box::new(command))),
+            );
+        }
+        list.push(item.padding([0, 10]).into());
+    }
+
+    let mut footer = row![].spacing(10).align_y(Alignment::Center);
+    if let Some(status) = state.status.as_ref() {
+        footer = footer.push(text(status.clone()).size(14));
+    } else if !conflicts.is_empty() {
+        let names: Vec<String> = conflicts
+            .iter()
+            .map(|command| command.to_string())
+            .collect();
+        footer = footer.push(
+            text(format!("Conflicts with {}", names.join(", ")))
+                .size(14)
+                .style(text::danger),
+        );
+    }
+    footer = footer.push(Space::with_width(Length::Fill));
+    if state.selected.is_some() {
+        let mut apply = button(text("Apply"));
+        if !state.captured.is_empty() && conflicts.is_empty() {
+            apply = apply.on_press(Message::ShortcutEditorApply);
+        }
+        footer = footer.push(apply).push(
+            button(text("Cancel"))
+                .style(button::secondary)
+                .on_press(Message::ShortcutEditorCancel),
+        );
+    } else {
+        footer = footer
+            .push(
+                button(text("Reset to Defaults"))
+                    .style(button::secondary)
+                    .on_press(Message::ShortcutEditorReset),
+            )
+            .push(button(text("Save")).on_press(Message::ShortcutEditorSave));
+    }
+
+    let window = container(
+        column![
+            text("Edit Keyboard Shortcuts").size(25),
+            scrollable(Column::from_vec(list).spacing(5)).height(Length::Fill),
+            footer,
+        ]
+        .spacing(10),
+    )
+    .padding(10)
+    .center(600)
+    .style(|theme: &Theme| container::Style {
+        background: Some(theme.palette().background.into()),
+        border: border::rounded(10),
+        ..container::Style::default()
+    });
+
+    stack![
+        base.into(),
+        opaque(
+            mouse_area(center(opaque(window)).style(|_theme| {
+                container::Style {
+                    background: Some(
+                        Color {
+                            a: 0.8,
+                            ..Color::BLACK
+                        }
+                        .into(),
+                    ),
+                    ..container::Style::default()
+                }
+            }))
+            .on_press(Message::HideShortcutEditor),
+        )
+    ]
+    .into()
+}
@@ -0,0 +1,86 @@
+use iced::widget::{center, column, container, mouse_area, opaque, row, scrollable, stack, text};
+use iced::widget::{Column, Space};
+use iced::{border, Color, Element, Length, Theme};
+
+use super::Message;
+use crate::shortcut::chord_to_string;
+use crate::{Chord, Shortcuts};
+
+/// Formats a shortcut's chords for display, e.g. `Ctrl + K  Ctrl + T`
+pub fn shortcut_keys_to_string(chords: &[Chord]) -> String {
+    chords
+        .iter()
+        .map(|chord| chord_to_string(chord))
+        .collect::<Vec<_>>()
+        .join("  ")
+}
+
+/// Creates an overlay on `base` listing every shortcut grouped by `Message::category`
+///
+/// Shortcuts for messages that aren't commands are listed under "Other"
+pub fn shortcut_help<'a>(
+    base: impl Into<Element<'a, Message>>,
+    shortcuts: &Shortcuts,
+) -> Element<'a, Message> {
+    let mut categories: Vec<&'static str> = Vec::new();
+    let category = |message: &Message| message.category().unwrap_or("Other");
+    for (message, _) in shortcuts {
+        if !categories.contains(&category(message)) {
+            categories.push(category(message));
+        }
+    }
+
+    let mut list: Vec<Element<Message>> = Vec::new();
+    if shortcuts.is_empty() {
+        list.push(text("No shortcuts configured").into());
+    }
+    for name in categories {
+        list.push(text(name).size(20).into());
+        for (message, chords) in shortcuts
+            .iter()
+            .filter(|(message, _)| category(message) == name)
+        {
+            list.push(
+                row![
+                    text(message.to_string()).size(16),
+                    Space::with_width(Length::Fill),
+                    text(shortcut_keys_to_string(chords)).size(16),
+                ]
+                .padding([0, 10])
+                .into(),
+            );
+        }
+    }
+
+    let window = container(column![
+        text("Keyboard Shortcuts").size(25),
+        scrollable(Column::from_vec(list).spacing(5)).height(Length::Fill)
+    ])
+    .padding(10)
+    .center(600)
+    .style(|theme: &Theme| container::Style {
+        background: Some(theme.palette().background.into()),
+        border: border::rounded(10),
+        ..container::Style::default()
+    });
+
+    stack![
+        base.into(),
+        opaque(
+            mouse_area(center(opaque(window)).style(|_theme| {
+                container::Style {
+                    background: Some(
+                        Color {
+                            a: 0.8,
+                            ..Color::BLACK
+                        }
+                        .into(),
+                    ),
+                    ..container::Style::default()
+                }
+            }))
+            .on_press(Message::HideShortcutHelp),
+        )
+    ]
+    .into()
+}
@@ -0,0 +1,56 @@
+use iced::widget::{container, row, stack, text, Row, Space};
+use iced::{border, Element, Length, Theme};
+
+use super::Message;
+
+/// Holds the infomation shown in status_bar
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatusBarState {
+    pub hovered_link: Option<String>,
+    pub tooltip: Option<String>,
+    pub load_progress: Option<f32>,
+    pub zoom: f32,
+}
+
+/// Creates status bar widget overlayed on the bottom left of `base`
+pub fn status_bar<'a>(
+    base: impl Into<Element<'a, Message>>,
+    state: StatusBarState,
+) -> Element<'a, Message> {
+    let mut items: Vec<Element<Message>> = Vec::new();
+
+    // the link target is more useful than the tooltip when both exist
+    if let Some(status) = state.hovered_link.or(state.tooltip) {
+        items.push(text(status).size(14).into());
+    }
+    if let Some(progress) = state.load_progress {
+        items.push(
+            text(format!("Loading {:.0}%", progress * 100.0))
+                .size(14)
+                .into(),
+        );
+    }
+    if (state.zoom - 1.0).abs() > f32::EPSILON {
+        items.push(text(format!("{:.0}%", state.zoom * 100.0)).size(14).into());
+    }
+
+    if items.is_empty() {
+        return base.into();
+    }
+
+    let status = container(Row::from_vec(items).spacing(10))
+        .padding([2, 8])
+        .style(|theme: &Theme| container::Style {
+            background: Some(theme.palette().background.into()),
+            border: border::rounded(4),
+            ..container::Style::default()
+        });
+
+    stack![
+        base.into(),
+        container(row![status, Space::with_width(Length::Fill)])
+            .align_bottom(Length::Fill)
+            .padding(2)
+    ]
+    .into()
+}
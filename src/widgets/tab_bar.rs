@@ -1,40 +1,54 @@
-use iced::widget::{row, tooltip, Button};
-use iced::{self, Element, Length};
+use iced::widget::{button, image, row, text, tooltip, Button, Row};
+use iced::{self, Alignment, Element, Theme};
 use iced_aw::core::icons::bootstrap::{icon_to_text, Bootstrap};
-use iced_aw::{TabBar as TB, TabLabel};
 
 use super::{Message, TabSelectionType};
 use crate::engines::{TabInfo, Tabs};
+use crate::Favicons;
 
-/// Creates Tab bar widget
-pub fn tab_bar<Info: TabInfo>(tabs: &Tabs<Info>) -> Element<Message> {
+/// Creates Tab bar widget, marking the tabs as private when `private` is set
+pub fn tab_bar<'a, Info: TabInfo>(
+    tabs: &'a Tabs<Info>,
+    favicons: &'a Favicons,
+    private: bool,
+) -> Element<'a, Message> {
     let current_id = tabs.get_current_id();
-    let active_tab = tabs
-        .tabs()
-        .iter()
-        .position(|tab| tab.id() == current_id)
-        .expect("Failed to find tab with that id");
 
-    let tab_bar = tabs
-        .tabs()
-        .iter()
-        .fold(
-            TB::new(|index| Message::ChangeTab(TabSelectionType::Index(index))),
-            |tab_bar, tab| {
-                let id = tab_bar.size();
+    let tab_bar = Row::from_vec(
+        tabs.tabs()
+            .iter()
+            .map(|tab| {
                 let title = if tab.title().is_empty() {
                     String::from("New Tab")
                 } else {
                     tab.title()
                 };
-                tab_bar.push(id, TabLabel::Text(title))
-            },
-        )
-        .set_active_tab(&active_tab)
-        .on_close(|index| Message::CloseTab(TabSelectionType::Index(index)))
-        .tab_width(Length::Shrink)
-        .spacing(5.0)
-        .padding(5.0);
+
+                let mut label = row![].spacing(5).align_y(Alignment::Center);
+                if let Some(icon) = favicons.get(&tab.url()) {
+                    label = label.push(image(icon).width(16).height(16));
+                }
+                label = label.push(text(title)).push(
+                    Button::new(icon_to_text(Bootstrap::X))
+                        .on_press(Message::CloseTab(TabSelectionType::Id(tab.id())))
+                        .style(button::text)
+                        .padding(0),
+                );
+
+                let style: fn(&Theme, button::Status) -> button::Style = if tab.id() == current_id {
+                    button::primary
+                } else {
+                    button::secondary
+                };
+                Button::new(label)
+                    .on_press(Message::ChangeTab(TabSelectionType::Id(tab.id())))
+                    .style(style)
+                    .into()
+            })
+            .collect(),
+    )
+    .spacing(5.0)
+    .padding(5.0);
 
     let new_tab = tooltip(
         Button::new(icon_to_text(Bootstrap::Plus))
@@ -44,5 +58,16 @@ pub fn tab_bar<Info: TabInfo>(tabs: &Tabs<Info>) -> Element<Message> {
         tooltip::Position::Bottom,
     );
 
-    row!(tab_bar, new_tab).into()
+    let mut row = row![].align_y(Alignment::Center);
+    if private {
+        row = row.push(
+            tooltip(
+                row![icon_to_text(Bootstrap::Incognito), text("Private")].spacing(5),
+                "Private browsing: history and site data are not kept",
+                tooltip::Position::Bottom,
+            )
+            .padding(5),
+        );
+    }
+    row.push(tab_bar).push(new_tab).into()
 }